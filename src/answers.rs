@@ -0,0 +1,102 @@
+use std::{collections::HashMap, fmt::Display, fs, path::Path, str::FromStr};
+
+use anyhow::{Context, Result, anyhow};
+
+use crate::solution::Answer;
+
+pub const ANSWERS_FILE: &str = "answers.txt";
+pub const EXAMPLE_ANSWERS_FILE: &str = "answers-example.txt";
+
+/// Result of the comparison between a computed answer and the known one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail { expected: String },
+    Unknown,
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Pass => write!(f, "PASS"),
+            Status::Fail { expected } => write!(f, "FAIL (expected {expected})"),
+            Status::Unknown => write!(f, "UNKNOWN"),
+        }
+    }
+}
+
+/// Known answers keyed by (day, part).
+///
+/// The file has one answer per line, `<day> <part> <answer>`,
+/// blank lines and lines starting with `#` are skipped.
+#[derive(Default)]
+pub struct Answers {
+    known: HashMap<(u8, u8), String>,
+}
+
+impl FromStr for Answers {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut known = HashMap::new();
+        for (n, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            let (Some(day), Some(part), Some(answer), None) =
+                (fields.next(), fields.next(), fields.next(), fields.next())
+            else {
+                return Err(anyhow!("line {}: expected `<day> <part> <answer>`", n + 1));
+            };
+            let day: u8 = day.parse().with_context(|| format!("line {}: wrong day '{day}'", n + 1))?;
+            let part: u8 = part.parse().with_context(|| format!("line {}: wrong part '{part}'", n + 1))?;
+            known.insert((day, part), answer.to_string());
+        }
+        Ok(Self { known })
+    }
+}
+
+impl Answers {
+    // a missing file simply means no answer is known yet
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let data = fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        Self::from_str(&data).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    pub fn verify(&self, day: u8, part: u8, answer: &Answer) -> Status {
+        match (self.known.get(&(day, part)), answer) {
+            (_, Answer::Unsolved) | (None, _) => Status::Unknown,
+            (Some(expected), answer) if *expected == answer.to_string() => Status::Pass,
+            (Some(expected), _) => Status::Fail { expected: expected.clone() },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_answers() {
+        let answers = Answers::from_str("# day part answer\n\n7 1 21\n7 2 40\n").unwrap();
+        assert_eq!(answers.verify(7, 1, &Answer::Number(21)), Status::Pass);
+        assert_eq!(
+            answers.verify(7, 2, &Answer::Number(41)),
+            Status::Fail { expected: "40".to_string() }
+        );
+        assert_eq!(answers.verify(8, 1, &Answer::Number(21)), Status::Unknown);
+        assert_eq!(answers.verify(7, 1, &Answer::Unsolved), Status::Unknown);
+    }
+
+    #[test]
+    fn wrong_answers_line() {
+        assert!(Answers::from_str("7 1").is_err());
+        assert!(Answers::from_str("seven 1 21").is_err());
+        assert!(Answers::from_str("7 1 21 22").is_err());
+    }
+}
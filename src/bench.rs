@@ -0,0 +1,163 @@
+use std::fmt::Display;
+use std::time::{Duration, Instant};
+
+use crate::parse::ParseError;
+use crate::solution::Solution;
+
+pub type BenchFn = fn(u8, &str, Option<u8>, &BenchConfig) -> Result<Vec<BenchReport>, ParseError>;
+
+/// How many times each phase is repeated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchConfig {
+    /// untimed runs before the measured ones
+    pub warmup: u32,
+    /// timed runs, at least 1
+    pub runs: u32,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self { warmup: 3, runs: 10 }
+    }
+}
+
+/// The measured step of a day: parsing the input or solving a part.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Parse,
+    Part(u8),
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Phase::Parse => write!(f, "parse"),
+            Phase::Part(n) => write!(f, "part {n}"),
+        }
+    }
+}
+
+/// Summary of the timings of repeated runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        assert!(!samples.is_empty(), "no samples to summarize");
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let n = sorted.len();
+        let median = if n.is_multiple_of(2) {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+        } else {
+            sorted[n / 2]
+        };
+        let mean = sorted.iter().map(Duration::as_secs_f64).sum::<f64>() / n as f64;
+        // sample standard deviation, zero for a single run
+        let variance = if n > 1 {
+            sorted.iter().map(|s| (s.as_secs_f64() - mean).powi(2)).sum::<f64>() / (n - 1) as f64
+        } else {
+            0.0
+        };
+        Self {
+            runs: n,
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+/// The timings of one phase of a day.
+pub struct BenchReport {
+    pub day: u8,
+    pub phase: Phase,
+    pub stats: Stats,
+}
+
+impl Display for BenchReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let stats = &self.stats;
+        write!(
+            f,
+            "Day {:>2} - {:<6}: min {:>10.2?}, median {:>10.2?}, mean {:>10.2?}, stddev {:>10.2?} ({} runs)",
+            self.day,
+            self.phase.to_string(),
+            stats.min,
+            stats.median,
+            stats.mean,
+            stats.stddev,
+            stats.runs
+        )
+    }
+}
+
+// time `f` config.runs times after config.warmup untimed calls
+fn measure<T>(config: &BenchConfig, mut f: impl FnMut() -> T) -> Stats {
+    for _ in 0..config.warmup {
+        std::hint::black_box(f());
+    }
+    let samples: Vec<Duration> = (0..config.runs.max(1))
+        .map(|_| {
+            let now = Instant::now();
+            std::hint::black_box(f());
+            now.elapsed()
+        })
+        .collect();
+    Stats::from_samples(&samples)
+}
+
+// parse and solve phases are measured separately,
+// each solve reuses a single parsed model
+pub fn bench<S: Solution>(
+    day: u8,
+    input: &str,
+    part: Option<u8>,
+    config: &BenchConfig,
+) -> Result<Vec<BenchReport>, ParseError> {
+    // fail early on a wrong input rather than inside the measures
+    let model = S::parse(input)?;
+    let mut reports = vec![BenchReport {
+        day,
+        phase: Phase::Parse,
+        stats: measure(config, || S::parse(input)),
+    }];
+    for n in [1, 2].into_iter().filter(|n| part.is_none_or(|p| p == *n)) {
+        let stats = match n {
+            1 => measure(config, || S::part_one(&model)),
+            _ => measure(config, || S::part_two(&model)),
+        };
+        reports.push(BenchReport { day, phase: Phase::Part(n), stats });
+    }
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|&v| Duration::from_millis(v)).collect()
+    }
+
+    #[test]
+    fn stats() {
+        let stats = Stats::from_samples(&millis(&[4, 2, 8, 6]));
+        assert_eq!(stats.runs, 4);
+        assert_eq!(stats.min, Duration::from_millis(2));
+        assert_eq!(stats.median, Duration::from_millis(5));
+        assert_eq!(stats.mean.as_micros(), 5000);
+        // sqrt(20 / 3) ms
+        assert_eq!(stats.stddev.as_micros(), 2581);
+
+        let single = Stats::from_samples(&millis(&[3]));
+        assert_eq!((single.median, single.stddev), (Duration::from_millis(3), Duration::ZERO));
+    }
+}
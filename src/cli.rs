@@ -0,0 +1,300 @@
+use std::{fmt::Display, num::ParseIntError, ops::RangeInclusive, str::FromStr};
+
+use anyhow::{Context, Result, anyhow, bail};
+
+use aoc_2025::bench::BenchConfig;
+use aoc_2025::input::{InputMode, STDIN};
+use aoc_2025::solution::TraceFormat;
+
+pub const USAGE: &str = "Usage:
+    aoc-2025 run --day <N> [--part <1|2>] [--example | --input <path> | <path> | -] [--answers <path>] [--format <text|json>]
+                 [--trace <path> [--trace-format <text|color|csv>]]
+    aoc-2025 run --all [--example] [--answers <path>] [--format <text|json>]
+    aoc-2025 bench --day <N> [--part <1|2>] [--example | --input <path> | <path> | -] [--runs <N>] [--warmup <N>] [--format <text|json>]
+    aoc-2025 bench --all [--example] [--runs <N>] [--warmup <N>] [--format <text|json>]
+    aoc-2025 help
+
+Options:
+    -d, --day <N>       day to run (1-12)
+    -p, --part <1|2>    part to run, both parts when omitted
+    -e, --example       use the examples input/example-dayN instead of input/input-dayN
+    -i, --input <path>  read the puzzle input from <path>, - reads the standard input;
+                        the path can also be given alone, as in `run -d 5 -`
+    -a, --all           run every implemented day in sequence
+    --answers <path>    check the answers against <path> (default answers.txt,
+                        or answers-example.txt with --example)
+    -f, --format <fmt>  output as text (default) or json
+    --trace <path>      run: write the steps of the solution to <path>, - for stdout
+                        (days 1 to 4, not with --format json)
+    --trace-format <f>  run: trace as text (default), color text or csv
+    --runs <N>          bench: timed runs of each phase (default 10)
+    --warmup <N>        bench: untimed runs before the timed ones (default 3)
+
+Input directives, lines of an input changing how a day reads it:
+    merge               day 2, first line: merge the overlapping ranges, an ID in several
+                        ranges counts once
+    connections <N>     day 8, any line: pairs of boxes connected in part one (default 1000,
+                        the example uses 10)";
+
+pub enum Command {
+    Run(RunArgs),
+    Bench(BenchArgs),
+    Help,
+}
+
+/// The trace path standing for the standard output.
+pub const TRACE_STDOUT: &str = "-";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+}
+
+pub enum Target {
+    All,
+    Day { day: u8, part: Option<u8> },
+}
+
+pub struct RunArgs {
+    pub target: Target,
+    pub mode: InputMode,
+    pub input: Option<String>,
+    pub answers: Option<String>,
+    pub format: Format,
+    pub trace: Option<String>,
+    pub trace_format: TraceFormat,
+}
+
+pub struct BenchArgs {
+    pub target: Target,
+    pub mode: InputMode,
+    pub input: Option<String>,
+    pub config: BenchConfig,
+    pub format: Format,
+}
+
+pub fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Command> {
+    match args.next().as_deref() {
+        Some("run") => parse_run(args).map(Command::Run),
+        Some("bench") => parse_bench(args).map(Command::Bench),
+        Some("help") | Some("-h") | Some("--help") | None => Ok(Command::Help),
+        Some(other) => bail!("unknown command '{other}'"),
+    }
+}
+
+// options of the run and bench commands, not all of them apply to both
+struct Options {
+    target: Target,
+    mode: InputMode,
+    input: Option<String>,
+    answers: Option<String>,
+    runs: Option<u32>,
+    warmup: Option<u32>,
+    format: Format,
+    trace: Option<String>,
+    trace_format: Option<TraceFormat>,
+}
+
+fn parse_run(args: impl Iterator<Item = String>) -> Result<RunArgs> {
+    let options = parse_options(args)?;
+    if options.runs.is_some() || options.warmup.is_some() {
+        bail!("--runs and --warmup only apply to bench");
+    }
+    if options.trace_format.is_some() && options.trace.is_none() {
+        bail!("--trace-format needs --trace");
+    }
+    if options.trace.is_some() && matches!(options.target, Target::All) {
+        bail!("--trace needs a single --day");
+    }
+    // the JSON array is the whole output
+    if options.trace.as_deref() == Some(TRACE_STDOUT) && options.format == Format::Json {
+        bail!("--trace - can't be combined with --format json, trace to a file instead");
+    }
+    Ok(RunArgs {
+        target: options.target,
+        mode: options.mode,
+        input: options.input,
+        answers: options.answers,
+        format: options.format,
+        trace: options.trace,
+        trace_format: options.trace_format.unwrap_or_default(),
+    })
+}
+
+fn parse_bench(args: impl Iterator<Item = String>) -> Result<BenchArgs> {
+    let options = parse_options(args)?;
+    if options.answers.is_some() {
+        bail!("--answers only applies to run");
+    }
+    if options.trace.is_some() || options.trace_format.is_some() {
+        bail!("--trace and --trace-format only apply to run");
+    }
+    let default = BenchConfig::default();
+    Ok(BenchArgs {
+        target: options.target,
+        mode: options.mode,
+        input: options.input,
+        config: BenchConfig {
+            runs: options.runs.unwrap_or(default.runs),
+            warmup: options.warmup.unwrap_or(default.warmup),
+        },
+        format: options.format,
+    })
+}
+
+fn parse_options(mut args: impl Iterator<Item = String>) -> Result<Options> {
+    let mut all = false;
+    let mut day = None;
+    let mut part = None;
+    let mut mode = InputMode::Normal;
+    let mut input = None;
+    let mut answers = None;
+    let mut runs = None;
+    let mut warmup = None;
+    let mut format = Format::Text;
+    let mut trace = None;
+    let mut trace_format = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-a" | "--all" => all = true,
+            "-e" | "--example" => mode = InputMode::Example,
+            "-d" | "--day" => day = Some(parse_number(&arg, args.next(), 1..=12)?),
+            "-p" | "--part" => part = Some(parse_number(&arg, args.next(), 1..=2)?),
+            "-i" | "--input" => {
+                let path = args.next().ok_or_else(|| anyhow!("{arg} expects a path"))?;
+                set_input(&mut input, path)?
+            }
+            "--answers" => {
+                answers = Some(args.next().ok_or_else(|| anyhow!("{arg} expects a path"))?)
+            }
+            "--runs" => runs = Some(parse_number(&arg, args.next(), 1..=u32::MAX)?),
+            "--warmup" => warmup = Some(parse_number(&arg, args.next(), 0..=u32::MAX)?),
+            "-f" | "--format" => {
+                format = match args.next().as_deref() {
+                    Some("text") => Format::Text,
+                    Some("json") => Format::Json,
+                    Some(other) => bail!("{arg}: unknown format '{other}', expected text or json"),
+                    None => bail!("{arg} expects text or json"),
+                }
+            }
+            "--trace" => trace = Some(args.next().ok_or_else(|| anyhow!("{arg} expects a path"))?),
+            "--trace-format" => {
+                trace_format = match args.next().as_deref() {
+                    Some("text") => Some(TraceFormat::Text),
+                    Some("color") => Some(TraceFormat::Color),
+                    Some("csv") => Some(TraceFormat::Csv),
+                    Some(other) => bail!("{arg}: unknown format '{other}', expected text, color or csv"),
+                    None => bail!("{arg} expects text, color or csv"),
+                }
+            }
+            // a lone "-" is the standard input, not a flag
+            _ if arg == STDIN || !arg.starts_with('-') => set_input(&mut input, arg)?,
+            _ => bail!("unknown argument '{arg}'"),
+        }
+    }
+
+    if mode == InputMode::Example && input.is_some() {
+        bail!("--example and --input are mutually exclusive");
+    }
+    let target = match (all, day) {
+        (true, None) => {
+            if part.is_some() || input.is_some() {
+                bail!("--all can't be combined with --part or --input");
+            }
+            Target::All
+        }
+        (false, Some(day)) => Target::Day { day, part },
+        (true, Some(_)) => bail!("--all and --day are mutually exclusive"),
+        (false, None) => bail!("either --day or --all is required"),
+    };
+    Ok(Options { target, mode, input, answers, runs, warmup, format, trace, trace_format })
+}
+
+fn set_input(input: &mut Option<String>, path: String) -> Result<()> {
+    if let Some(previous) = input {
+        bail!("two inputs given, '{previous}' and '{path}'");
+    }
+    *input = Some(path);
+    Ok(())
+}
+
+fn parse_number<T>(flag: &str, value: Option<String>, range: RangeInclusive<T>) -> Result<T>
+where
+    T: FromStr<Err = ParseIntError> + PartialOrd + Display,
+{
+    let value = value.ok_or_else(|| anyhow!("{flag} expects a number"))?;
+    let n: T = value
+        .parse()
+        .with_context(|| format!("{flag}: '{value}' is not a number"))?;
+    if !range.contains(&n) {
+        bail!("{flag}: {n} is out of range {}-{}", range.start(), range.end());
+    }
+    Ok(n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> impl Iterator<Item = String> {
+        line.split_whitespace().map(String::from).collect::<Vec<_>>().into_iter()
+    }
+
+    #[test]
+    fn run_day_and_part() {
+        let Command::Run(run) = parse_args(args("run -d 7 --part 2 -i input/x")).unwrap() else {
+            panic!("expected a run command");
+        };
+        assert!(matches!(run.target, Target::Day { day: 7, part: Some(2) }));
+        assert_eq!(run.input.as_deref(), Some("input/x"));
+        assert_eq!(run.format, Format::Text);
+
+        let Command::Run(run) = parse_args(args("run -d 5 -p 2 -")).unwrap() else {
+            panic!("expected a run command");
+        };
+        assert_eq!(run.input.as_deref(), Some("-"));
+        let Command::Run(run) = parse_args(args("run input/x -d 5")).unwrap() else {
+            panic!("expected a run command");
+        };
+        assert_eq!(run.input.as_deref(), Some("input/x"));
+
+        let Command::Run(run) = parse_args(args("run -d 1 -e --trace - --trace-format csv")).unwrap() else {
+            panic!("expected a run command");
+        };
+        assert_eq!(run.trace.as_deref(), Some("-"));
+        assert_eq!(run.trace_format, TraceFormat::Csv);
+    }
+
+    #[test]
+    fn wrong_arguments() {
+        assert!(parse_args(args("run")).is_err());
+        assert!(parse_args(args("run --day 13")).is_err());
+        assert!(parse_args(args("run --all --day 2")).is_err());
+        assert!(parse_args(args("run --day 2 --part")).is_err());
+        assert!(parse_args(args("run --day 2 --example --input input/x")).is_err());
+        assert!(parse_args(args("run --day 2 --runs 5")).is_err());
+        assert!(parse_args(args("bench --day 2 --runs 0")).is_err());
+        assert!(parse_args(args("bench --all --answers answers.txt")).is_err());
+        assert!(parse_args(args("run --all --format yaml")).is_err());
+        assert!(parse_args(args("run --day 2 -i input/x input/y")).is_err());
+        assert!(parse_args(args("run --all -")).is_err());
+        assert!(parse_args(args("run --all --trace -")).is_err());
+        assert!(parse_args(args("run --day 1 --format json --trace -")).is_err());
+        assert!(parse_args(args("run --day 1 --format json --trace day1.csv")).is_ok());
+        assert!(parse_args(args("run --day 1 --trace-format csv")).is_err());
+        assert!(parse_args(args("bench --day 1 --trace -")).is_err());
+    }
+
+    #[test]
+    fn bench_runs() {
+        let Command::Bench(bench) = parse_args(args("bench -a --runs 50 --warmup 0 -f json")).unwrap() else {
+            panic!("expected a bench command");
+        };
+        assert!(matches!(bench.target, Target::All));
+        assert_eq!(bench.config, BenchConfig { warmup: 0, runs: 50 });
+        assert_eq!(bench.format, Format::Json);
+    }
+}
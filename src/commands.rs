@@ -0,0 +1,166 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+
+use aoc_2025::DAYS;
+use aoc_2025::answers::{self, Answers, Status};
+use aoc_2025::input::{InputMode, input_name, input_path, load_input};
+use aoc_2025::runner::{Day, PartReport};
+use aoc_2025::solution::{Answer, TraceFormat};
+
+use crate::cli::{BenchArgs, Format, RunArgs, TRACE_STDOUT, Target};
+use crate::json::{self, Json};
+
+// where a run takes its input from
+enum Source<'a> {
+    Mode(InputMode),
+    File(&'a str),
+}
+
+// the input files of a day with the parts solved from each of them,
+// the parts sharing the same input file are parsed once
+fn day_inputs(day: &Day, part: Option<u8>, source: &Source) -> Vec<(PathBuf, Option<u8>)> {
+    match (source, part) {
+        (Source::File(path), part) => vec![(PathBuf::from(path), part)],
+        (Source::Mode(mode), Some(part)) => vec![(input_path(day.number, part, *mode), Some(part))],
+        (Source::Mode(mode), None) => {
+            let (part_one, part_two) = (input_path(day.number, 1, *mode), input_path(day.number, 2, *mode));
+            if part_one == part_two {
+                vec![(part_one, None)]
+            } else {
+                vec![(part_one, Some(1)), (part_two, Some(2))]
+            }
+        }
+    }
+}
+
+// the days selected by a target, with the part to solve
+fn selected_days(target: &Target, mode: InputMode) -> Vec<(&'static Day, Option<u8>)> {
+    match *target {
+        Target::All => DAYS
+            .iter()
+            .filter(|day| {
+                // only the days we have an input for
+                let path = input_path(day.number, 1, mode);
+                // a note on stderr, so it never mixes with the json output
+                if !path.exists() {
+                    eprintln!("Day {:>2} - skipped, {} not found", day.number, path.display());
+                }
+                path.exists()
+            })
+            .map(|day| (day, None))
+            .collect(),
+        Target::Day { day, part } => vec![(&DAYS[day as usize - 1], part)],
+    }
+}
+
+fn source(input: &Option<String>, mode: InputMode) -> Source<'_> {
+    match input {
+        Some(path) => Source::File(path),
+        None => Source::Mode(mode),
+    }
+}
+
+// where the steps of a solution are written
+struct Tracer {
+    format: TraceFormat,
+    out: Box<dyn Write>,
+}
+
+impl Tracer {
+    fn create(path: &str, format: TraceFormat) -> anyhow::Result<Self> {
+        let out: Box<dyn Write> = if path == TRACE_STDOUT {
+            Box::new(io::stdout())
+        } else {
+            let file = File::create(path).with_context(|| format!("can't create trace file {path}"))?;
+            Box::new(BufWriter::new(file))
+        };
+        Ok(Self { format, out })
+    }
+}
+
+// solve the parts of a day and check them against the known answers
+fn run_day(
+    day: &Day,
+    part: Option<u8>,
+    source: &Source,
+    answers: &Answers,
+    mut tracer: Option<&mut Tracer>,
+) -> anyhow::Result<Vec<(PartReport, Status)>> {
+    let mut results = vec![];
+    for (path, part) in day_inputs(day, part, source) {
+        let input = load_input(&path)?;
+        let reports = (day.run)(day.number, &input, part)
+            .with_context(|| format!("parsing {}", input_name(&path)))?;
+        if let Some(tracer) = tracer.as_deref_mut() {
+            if !(day.trace)(&input, tracer.format, &mut tracer.out)? {
+                anyhow::bail!("day {} has no trace", day.number);
+            }
+            tracer.out.flush().context("can't write the trace")?;
+        }
+        for report in reports {
+            let status = answers.verify(report.day, report.part, &report.answer);
+            results.push((report, status));
+        }
+    }
+    Ok(results)
+}
+
+pub fn run(args: RunArgs) -> anyhow::Result<bool> {
+    // known answers belong to the default inputs, a custom input
+    // is checked only against an explicit answers file
+    let answers = match (&args.answers, &args.input, args.mode) {
+        (Some(path), _, _) => Answers::load(Path::new(path))?,
+        (None, Some(_), _) => Answers::default(),
+        (None, None, InputMode::Normal) => Answers::load(Path::new(answers::ANSWERS_FILE))?,
+        (None, None, InputMode::Example) => Answers::load(Path::new(answers::EXAMPLE_ANSWERS_FILE))?,
+    };
+    let source = source(&args.input, args.mode);
+    let mut tracer = match &args.trace {
+        Some(path) => Some(Tracer::create(path, args.trace_format)?),
+        None => None,
+    };
+    // return false when any answer doesn't match the known one,
+    // or can't be computed from the input
+    let mut all_good = true;
+    let mut output = vec![];
+    for (day, part) in selected_days(&args.target, args.mode) {
+        for (report, status) in run_day(day, part, &source, &answers, tracer.as_mut())? {
+            if let (Status::Fail { .. }, _) | (_, Answer::Error(_)) = (&status, &report.answer) {
+                all_good = false;
+            }
+            match args.format {
+                Format::Text => println!("{report} {status}"),
+                Format::Json => output.push(json::part_report(&report, &status)),
+            }
+        }
+    }
+    if args.format == Format::Json {
+        println!("{}", Json::Array(output));
+    }
+    Ok(all_good)
+}
+
+pub fn bench(args: BenchArgs) -> anyhow::Result<()> {
+    let source = source(&args.input, args.mode);
+    let mut output = vec![];
+    for (day, part) in selected_days(&args.target, args.mode) {
+        for (path, part) in day_inputs(day, part, &source) {
+            let input = load_input(&path)?;
+            let reports = (day.bench)(day.number, &input, part, &args.config)
+                .with_context(|| format!("parsing {}", input_name(&path)))?;
+            for report in reports {
+                match args.format {
+                    Format::Text => println!("{report}"),
+                    Format::Json => output.push(json::bench_report(&report)),
+                }
+            }
+        }
+    }
+    if args.format == Format::Json {
+        println!("{}", Json::Array(output));
+    }
+    Ok(())
+}
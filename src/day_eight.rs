@@ -1,26 +1,40 @@
 #![allow(dead_code)]
 
+use std::str::FromStr;
+
+use crate::parse::{ParseError, parse_lines};
+use crate::solution::{Answer, Solution};
+
 #[derive(Clone, Eq, PartialEq, Debug)]
-struct JBox {
-    x: i64,
-    y: i64,
-    z: i64,
+pub struct JBox {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
 }
 
-impl JBox {
-    fn from_str(input: &str) -> Self {
-        let xyz: Vec<&str> = input.splitn(3, ",").collect();
+impl FromStr for JBox {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let xyz: Vec<&str> = input.trim_end().splitn(3, ",").collect();
+        let coordinate = |value: &str, name: &str| {
+            value
+                .parse::<i64>()
+                .map_err(|e| ParseError::at(input, value, format!("{name} '{value}' is not a number: {e}")))
+        };
         let (x, y, z): (i64, i64, i64) = match &xyz[..] {
             &[first, second, third, ..] => (
-                first.parse().expect("X is not a number"),
-                second.parse().expect("Y is not a number"),
-                third.parse().expect("Z is not a number"),
+                coordinate(first, "X")?,
+                coordinate(second, "Y")?,
+                coordinate(third, "Z")?,
             ),
-            _ => unreachable!(),
+            _ => return Err(ParseError::at(input, input, "expected the X,Y,Z coordinates")),
         };
-        Self { x, y, z }
+        Ok(Self { x, y, z })
     }
+}
 
+impl JBox {
     pub fn distance(&self, other: &JBox) -> f64 {
         let dx = (self.x - other.x) as f64;
         let dy = (self.y - other.y) as f64;
@@ -51,22 +65,22 @@ impl Circuit {
 
     // return the added JBox
     fn try_add<'a>(&mut self, a: &'a JBox, b: &'a JBox) -> IsInCircuit<'a> {
-        if self.links.contains(&a) && !self.links.contains(&b) {
+        if self.links.contains(a) && !self.links.contains(b) {
             self.links.push(b.clone());
             return IsInCircuit::One(b);
         }
-        if self.links.contains(&b) && !self.links.contains(&a) {
+        if self.links.contains(b) && !self.links.contains(a) {
             self.links.push(a.clone());
             return IsInCircuit::One(a);
         }
-        if self.links.contains(&a) && self.links.contains(&b) {
+        if self.links.contains(a) && self.links.contains(b) {
             return IsInCircuit::Two(a, b);
         }
-        return IsInCircuit::None;
+        IsInCircuit::None
     }
 
     fn contains(&self, jbox: &JBox) -> bool {
-        self.links.contains(&jbox)
+        self.links.contains(jbox)
     }
 
     fn get_size(&self) -> usize {
@@ -78,27 +92,51 @@ impl Circuit {
     }
 }
 
-enum Part {
-    One,
-    Two,
+// the puzzle input connects its thousand closest pairs of boxes
+pub const DEFAULT_CONNECTIONS: usize = 1000;
+
+/// The junction boxes, and how many of their closest pairs part one connects.
+///
+/// The input has a box `X,Y,Z` per line. A line `connections <N>` changes
+/// the pairs connected in part one, 1000 by default: the example uses 10.
+#[derive(Debug, Clone)]
+pub struct Playground {
+    pub boxes: Vec<JBox>,
+    pub connections: usize,
 }
 
-fn generate_circuits<'a>(connections: &'a [(f64, &JBox, &JBox)], part: Part) -> Vec<Circuit> {
+impl Playground {
+    pub fn from_input(input: &str) -> Result<Self, ParseError> {
+        let mut connections = None;
+        let mut boxes = vec![];
+        for line in input.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let Some(count) = line.strip_prefix("connections ") else {
+                boxes.push(JBox::from_str(line).map_err(|e| e.within(input, line))?);
+                continue;
+            };
+            if connections.is_some() {
+                return Err(ParseError::at(input, line, "the connections are given twice"));
+            }
+            let count = count.trim();
+            connections = Some(count.parse().map_err(|e| {
+                ParseError::at(input, count, format!("'{count}' is not a number of connections: {e}"))
+            })?);
+        }
+        Ok(Self { boxes, connections: connections.unwrap_or(DEFAULT_CONNECTIONS) })
+    }
+}
+
+// return the circuits and the x coordinates of the last two linked boxes
+fn generate_circuits(connections: &[(f64, &JBox, &JBox)]) -> (Vec<Circuit>, (i64, i64)) {
     // initialized with random value
     let mut last_linked_boxes: (i64, i64) = (0, 0);
 
     let mut circuits: Vec<Circuit> = vec![];
 
-    // connections
-    let available_connections = match part {
-        Part::One => &connections[..1000],
-        Part::Two => &connections,
-    };
-
-    for (_, box_a, box_b) in available_connections {
+    for (_, box_a, box_b) in connections {
         // look for bridge link to merge circuits
-        let a_is_on_circuit = circuits.iter().cloned().find(|c| c.contains(box_a));
-        let b_is_on_circuit = circuits.iter().cloned().find(|c| c.contains(box_b));
+        let a_is_on_circuit = circuits.iter().find(|&c| c.contains(box_a)).cloned();
+        let b_is_on_circuit = circuits.iter().find(|&c| c.contains(box_b)).cloned();
         if let Some(a) = a_is_on_circuit
             && let Some(b) = b_is_on_circuit
             && a.id != b.id
@@ -113,8 +151,8 @@ fn generate_circuits<'a>(connections: &'a [(f64, &JBox, &JBox)], part: Part) ->
             // println!("Bridged Circuits #{} to #{} with link {:?} {:?}", a.id, b.id, box_a, box_b);
         }
         let mut in_circuit = IsInCircuit::None;
-        for n in 0..circuits.len() {
-            in_circuit = circuits[n].try_add(box_a, box_b);
+        for circuit in circuits.iter_mut() {
+            in_circuit = circuit.try_add(box_a, box_b);
             match in_circuit {
                 IsInCircuit::One(_) => {
                     last_linked_boxes = (box_a.x, box_b.x);
@@ -132,25 +170,18 @@ fn generate_circuits<'a>(connections: &'a [(f64, &JBox, &JBox)], part: Part) ->
         }
     }
 
-    if let Part::Two = part {
-        println!("Last Linked Boxes: {last_linked_boxes:?}");
-        println!(
-            "Output part2: {}",
-            last_linked_boxes.0 * last_linked_boxes.1
-        );
-    }
-    circuits
+    (circuits, last_linked_boxes)
 }
 //very very raw id generator for circuits
 fn get_new_circuit_id(circuits: &[Circuit]) -> usize {
-    let new_id = if let Some(x) = circuits.last() {
+    
+    if let Some(x) = circuits.last() {
         x.id + 1
     } else {
         1
-    };
-    new_id
+    }
 }
-fn get_connections<'a>(boxes: &'a [JBox]) -> Vec<(f64, &'a JBox, &'a JBox)> {
+fn get_connections(boxes: &[JBox]) -> Vec<(f64, &JBox, &JBox)> {
     // store all possible links
     let mut connections: Vec<(f64, &JBox, &JBox)> = Vec::new();
     for (left_n, left_box) in boxes.iter().enumerate() {
@@ -164,41 +195,110 @@ fn get_connections<'a>(boxes: &'a [JBox]) -> Vec<(f64, &'a JBox, &'a JBox)> {
     }
     // sort link on distance
     connections.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
-    return connections;
+    connections
 }
 
-fn parse_boxes() -> Vec<JBox> {
-    let input = super::load_input("input/input-day8");
-    let lines: Vec<&str> = input.lines().collect();
-    let mut boxes = Vec::with_capacity(lines.len());
-    for line in lines {
-        boxes.push(JBox::from_str(line));
-    }
-    return boxes;
+pub fn parse_boxes(input: &str) -> Result<Vec<JBox>, ParseError> {
+    parse_lines(input, JBox::from_str)
 }
 
-pub fn part_one() {
-    let boxes = parse_boxes();
-    let connections = get_connections(&boxes);
+// product of the sizes of the three largest circuits once the `count`
+// closest pairs are connected, a box left alone is a circuit of size one
+pub fn largest_circuits_product(boxes: &[JBox], count: usize) -> usize {
+    let connections = get_connections(boxes);
 
-    let mut circuits = generate_circuits(&connections, Part::One);
+    let (mut circuits, _) = generate_circuits(&connections[..count.min(connections.len())]);
 
     // sort circuits by size
-    circuits.sort_by(|a, b| b.get_size().cmp(&a.get_size()));
+    circuits.sort_by_key(|c| std::cmp::Reverse(c.get_size()));
 
     let mut output = 1usize;
-    for circuit in &circuits[..3] {
+    for circuit in circuits.iter().take(3) {
         let circuit_size = circuit.get_size();
         // println!("Circuit #{} lenght: {} - {:?}", circuit.id, circuit_size, circuit.links);
-        println!("Circuit #{} lenght: {}", circuit.id, circuit_size);
         output *= circuit_size;
     }
-    println!("\nOutput first part: {}", output);
+    output
 }
 
-pub fn part_two() {
-    let boxes = parse_boxes();
-    let connections = get_connections(&boxes);
+// product of the x coordinates of the last two boxes linked in a single circuit
+/// Product of the X coordinates of the last two boxes linked,
+/// negative when one of them is left of the origin.
+pub fn last_connection_product(boxes: &[JBox]) -> i128 {
+    let connections = get_connections(boxes);
 
-    let _ = generate_circuits(&connections, Part::Two);
+    let (_, last_linked_boxes) = generate_circuits(&connections);
+    i128::from(last_linked_boxes.0) * i128::from(last_linked_boxes.1)
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Model = Playground;
+
+    fn parse(input: &str) -> Result<Self::Model, ParseError> {
+        Playground::from_input(input)
+    }
+
+    fn part_one(playground: &Self::Model) -> Answer {
+        largest_circuits_product(&playground.boxes, playground.connections).into()
+    }
+
+    fn part_two(playground: &Self::Model) -> Answer {
+        let product = last_connection_product(&playground.boxes);
+        match u64::try_from(product) {
+            Ok(product) => product.into(),
+            Err(_) => Answer::Text(product.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn circuits() {
+        let test_input = "connections 10
+162,817,812
+57,618,57
+906,360,560
+592,479,940
+352,342,300
+466,668,158
+542,29,236
+431,825,988
+739,650,466
+52,470,668
+216,146,977
+819,987,18
+117,168,530
+805,96,715
+346,949,466
+970,615,88
+941,993,340
+862,61,35
+984,92,344
+425,690,689";
+        let boxes = Solver::parse(test_input).unwrap();
+        assert_eq!(Solver::part_one(&boxes), Answer::Number(40));
+        assert_eq!(Solver::part_two(&boxes), Answer::Number(25272));
+        assert_eq!(largest_circuits_product(&boxes.boxes, 1), 2);
+    }
+
+    #[test]
+    fn small_inputs() {
+        // 6 pairs, fewer than the default thousand: all boxes are linked
+        let playground = Solver::parse("1,2,3\n4,5,6\n7,8,9\n10,11,12\n").unwrap();
+        assert_eq!(playground.connections, DEFAULT_CONNECTIONS);
+        assert_eq!(Solver::part_one(&playground), Answer::Number(4));
+        // two circuits of two boxes, no third one
+        let boxes = Solver::parse("0,0,0\n0,0,1\n50,0,0\n50,0,2\n").unwrap().boxes;
+        assert_eq!(largest_circuits_product(&boxes, 2), 4);
+        assert!(Solver::parse("connections 10\nconnections 20\n1,2,3").is_err());
+        assert!(Solver::parse("connections ten\n1,2,3").is_err());
+        // the last link joins -3 and 4
+        let playground = Solver::parse("-3,0,0\n5,0,0\n4,0,0").unwrap();
+        assert_eq!(Solver::part_two(&playground), Answer::Text("-12".to_string()));
+    }
 }
@@ -3,9 +3,10 @@
 use std::collections::HashMap;
 use std::{cell::RefCell, fmt::Display, rc::Rc};
 
-use crate::InputMode;
+use crate::parse::{ParseError, parse_lines};
+use crate::solution::{Answer, Solution};
 
-type NodeId = [char; 3];
+pub type NodeId = [char; 3];
 type Path = Vec<Rc<RefCell<Node>>>;
 
 struct Node {
@@ -20,18 +21,29 @@ impl Display for Node {
         for link in self.output_links.iter() {
             output = format!("{} {}", output, format_id((*link).borrow().id));
         }
-        write!(f, "{}\n", output)
+        writeln!(f, "{}", output)
     }
 }
 
+// node ids are made of three chars
+pub fn parse_node_id(input: &str, field: &str) -> Result<NodeId, ParseError> {
+    field
+        .chars()
+        .collect::<Vec<_>>()
+        .try_into()
+        .map_err(|_| ParseError::at(input, field, format!("'{field}' is not a node id of three chars")))
+}
+
 impl Node {
-    fn from_str(input: &str) -> Self {
-        let (part_one, _) = input.split_once(":").unwrap();
-        let id: NodeId = part_one.chars().collect::<Vec<_>>().try_into().unwrap();
-        Self {
+    fn from_str(input: &str) -> Result<Self, ParseError> {
+        let (part_one, _) = input
+            .split_once(":")
+            .ok_or_else(|| ParseError::at(input, input, "expected <node>: <outputs>"))?;
+        let id = parse_node_id(input, part_one)?;
+        Ok(Self {
             id,
             output_links: vec![],
-        }
+        })
     }
     fn add_connection(&mut self, new_link: Rc<RefCell<Node>>) {
         self.output_links.push(new_link);
@@ -44,7 +56,7 @@ impl PartialEq for Node {
 }
 
 impl Eq for Node {}
-struct Graph {
+pub struct Graph {
     nodes: Vec<Rc<RefCell<Node>>>,
     memo: RefCell<HashMap<(NodeId, NodeId), u64>>,
 
@@ -59,50 +71,55 @@ impl Graph {
     }
 
     fn get_node(&self, key: NodeId) -> Option<Rc<RefCell<Node>>> {
-        return self.nodes.iter().find_map(|n| {
+        self.nodes.iter().find_map(|n| {
             if n.borrow().id == key {
                 Some(n.clone())
             } else {
                 None
             }
-        });
+        })
     }
 
     fn add_node(&mut self, node: Node) -> bool {
         if self.nodes.iter().any(|n| n.borrow().id == node.id) {
-            return false;
+            false
         } else {
             self.nodes.push(Rc::new(RefCell::new(node)));
-            return true;
+            true
         }
     }
 
-    fn add_connections_from_str(&mut self, input_line: &str) -> Result<String, String> {
-        let (part_one, part_two) = input_line.split_once(":").unwrap();
-        let parent_id: NodeId = part_one.chars().collect::<Vec<_>>().try_into().unwrap();
+    fn add_connections_from_str(&mut self, input_line: &str) -> Result<(), ParseError> {
+        let (part_one, part_two) = input_line
+            .split_once(":")
+            .ok_or_else(|| ParseError::at(input_line, input_line, "expected <node>: <outputs>"))?;
+        let parent_id = parse_node_id(input_line, part_one)?;
         let parent_node = if let Some(n) = self.get_node(parent_id) {
             n
         } else {
-            return Result::Err("Parent node {part_one} not found".to_string());
+            return Err(ParseError::at(input_line, part_one, format!("node {part_one} not found")));
         };
 
-        for link in part_two.trim().split(" ") {
-            let child_id: NodeId = link.chars().collect::<Vec<_>>().try_into().unwrap();
+        for link in part_two.split_whitespace() {
+            let child_id = parse_node_id(input_line, link)?;
             let child_node = if let Some(n) = self.get_node(child_id) {
                 n
             } else {
-                return Err("Child node {link} not found".to_string());
+                return Err(ParseError::at(input_line, link, format!("node {link} is not defined")));
             };
             parent_node.borrow_mut().add_connection(child_node.clone());
         }
-        return Ok("Connections created".to_string());
+        Ok(())
     }
 
-    fn pathfinding(&self, from: NodeId, to: NodeId) -> u64 {
+    pub fn pathfinding(&self, from: NodeId, to: NodeId) -> u64 {
         if let Some(&cached) = self.memo.borrow().get(&(from, to)) {
             return cached;
         }
-        let frontier = self.get_node(from).unwrap();
+        // no path leaves a node missing from the graph
+        let Some(frontier) = self.get_node(from) else {
+            return 0;
+        };
         if frontier.borrow().id == to {
             self.memo.borrow_mut().insert((from, to), 1);
             return 1;
@@ -128,7 +145,7 @@ impl Display for Graph {
     }
 }
 
-fn generate_graph(input: String) -> Graph {
+pub fn generate_graph(input: &str) -> Result<Graph, ParseError> {
     // first create all nodes:
     let out_node = Node {
         id: ['o', 'u', 't'],
@@ -136,46 +153,79 @@ fn generate_graph(input: String) -> Graph {
     };
     let mut graph = Graph::new();
     graph.add_node(out_node);
-    for line in input.lines() {
-        graph.add_node(Node::from_str(line));
+    for node in parse_lines(input, Node::from_str)? {
+        graph.add_node(node);
     }
     // create connections
-    for line in input.lines() {
-        _ = graph.add_connections_from_str(line);
-    }
-    graph
+    parse_lines(input, |line| graph.add_connections_from_str(line))?;
+    Ok(graph)
 }
 
-fn parse(mode: InputMode) -> Graph {
-    let input: String;
-    match mode {
-        InputMode::Example => {
-            input = "".to_string();
-        }
-        InputMode::Normal => {
-            input = super::load_input("input/input-day11");
-        }
+pub struct Solver;
+
+impl Solution for Solver {
+    type Model = Graph;
+
+    fn parse(input: &str) -> Result<Self::Model, ParseError> {
+        generate_graph(input)
+    }
+
+    fn part_one(graph: &Self::Model) -> Answer {
+        // println!("{graph}");
+        let output = graph.pathfinding(['y', 'o', 'u'], ['o', 'u', 't']);
+        // for path in paths.iter() {
+        //     for node in path.iter() {
+        //         println!("{}", node.borrow());
+        //     }
+        // }
+        output.into()
     }
-    generate_graph(input)
-}
 
-pub fn part_one() {
-    let graph = parse(InputMode::Normal);
-    // println!("{graph}");
-    let output = graph.pathfinding(['y', 'o', 'u'], ['o', 'u', 't']);
-    // for path in paths.iter() {
-    //     for node in path.iter() {
-    //         println!("{}", node.borrow());
-    //     }
-    // }
-    println!("Part One Output: {}", output);
+    fn part_two(graph: &Self::Model) -> Answer {
+        // println!("{graph}");
+        let output_1 = graph.pathfinding(['s', 'v', 'r'], ['f', 'f', 't']);
+        let output_2 = graph.pathfinding(['f', 'f', 't'], ['d', 'a', 'c']);
+        let output_3 = graph.pathfinding(['d', 'a', 'c'], ['o', 'u', 't']);
+        (output_1 * output_2 * output_3).into()
+    }
 }
 
-pub fn part_two() {
-    let graph = parse(InputMode::Normal);
-    // println!("{graph}");
-    let output_1 = graph.pathfinding(['s', 'v', 'r'], ['f', 'f', 't']);
-    let output_2 = graph.pathfinding(['f', 'f', 't'], ['d', 'a', 'c']);
-    let output_3 = graph.pathfinding(['d', 'a', 'c'], ['o', 'u', 't']);
-    println!("Part Two Output: {}", output_1 * output_2 * output_3);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths() {
+        let test_input = "aaa: you hhh
+you: bbb ccc
+bbb: ddd eee
+ccc: ddd eee fff
+ddd: ggg
+eee: out
+fff: out
+ggg: out
+hhh: ccc fff iii
+iii: out";
+        let graph = Solver::parse(test_input).unwrap();
+        assert_eq!(Solver::part_one(&graph), Answer::Number(5));
+    }
+
+    #[test]
+    fn paths_through_dac_and_fft() {
+        let test_input = "svr: aaa bbb
+aaa: fft
+fft: ccc
+bbb: tty
+tty: ccc
+ccc: ddd eee
+ddd: hub
+hub: fff
+eee: dac
+dac: fff
+fff: ggg hhh
+ggg: out
+hhh: out";
+        let graph = Solver::parse(test_input).unwrap();
+        assert_eq!(Solver::part_two(&graph), Answer::Number(2));
+    }
 }
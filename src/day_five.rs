@@ -1,60 +1,72 @@
 #![allow(unused)]
 use std::collections::HashSet;
 
+use crate::parse::ParseError;
+use crate::solution::{Answer, Solution};
+
 #[derive(Debug, Clone, Copy)]
-struct Range {
-    min: u64,
-    max: u64
+pub struct Range {
+    pub min: u64,
+    pub max: u64
 }
 
 impl Range {
-    fn in_between(&self, n: u64) -> bool {
+    pub fn in_between(&self, n: u64) -> bool {
         n >= self.min && n <= self.max
     }
     
-    fn merge(&self, new_range: &Self) -> Option<Self> {
+    pub fn merge(&self, new_range: &Self) -> Option<Self> {
         if self.min <= new_range.min && self.max >= new_range.max {
-            return Some(Self { min: self.min, max: self.max });
+            Some(Self { min: self.min, max: self.max })
         } else if new_range.min <= self.min && new_range.max >= self.max {
-            return Some(Self {min: new_range.min, max: new_range.max });
+            Some(Self {min: new_range.min, max: new_range.max })
         } else if self.max >= new_range.min && self.max <= new_range.max {
-            return Some(Self { min: self.min, max: new_range.max });
+            Some(Self { min: self.min, max: new_range.max })
         }
         else if self.min <= new_range.max && self.min >= new_range.min {
-            return Some(Self {min: new_range.min, max: self.max });
+            Some(Self {min: new_range.min, max: self.max })
         } else {
-            return None;
+            None
         }
     }
 }
 
-struct Database {
+#[derive(Clone)]
+pub struct Database {
     fresh_ranges: Vec<Range>,
     ids: Vec<u64>
 }
 
 impl Database {
-    fn from_input(input: &str) -> Self {
-        let (part1, part2) = input.split_once("\n\n").expect("Hello wrong input!");
+    pub fn from_input(input: &str) -> Result<Self, ParseError> {
+        let (part1, part2) = input.split_once("\n\n").ok_or_else(|| {
+            ParseError::at(input, &input[input.len()..], "missing the blank line between ranges and IDs")
+        })?;
+        let parse_id = |id: &str| {
+            let id = id.trim();
+            id.parse::<u64>()
+                .map_err(|e| ParseError::at(input, id, format!("'{id}' is not an ID: {e}")))
+        };
         let mut fresh_ranges = vec![];
         let mut ids = vec![];
         for line in part1.trim().split("\n") {
-            if let Some(ranges) = line.split_once("-") {
-                fresh_ranges.push(
-                    Range { min: ranges.0.parse().unwrap(), max: ranges.1.parse().unwrap()}
-                );
-            } 
-        }
-        for line in part2.trim().split("\n") {
-            if let Ok(id) = line.parse::<u64>() {
-                ids.push(id);
+            let Some((min, max)) = line.split_once("-") else {
+                return Err(ParseError::at(input, line, format!("'{line}' is not a range, expected <min>-<max>")));
+            };
+            let range = Range { min: parse_id(min)?, max: parse_id(max)? };
+            if range.min > range.max {
+                return Err(ParseError::at(input, line, format!("range {line} ends before it starts")));
             }
+            fresh_ranges.push(range);
+        }
+        for line in part2.trim().split("\n").filter(|l| !l.trim().is_empty()) {
+            ids.push(parse_id(line)?);
         }
 
-        Self { fresh_ranges, ids }
+        Ok(Self { fresh_ranges, ids })
     }
 
-    fn part_one(&self) -> u32 {
+    pub fn part_one(&self) -> u32 {
         let mut output = 0u32; 
         for id in self.ids.iter() {
             if self.fresh_ranges.iter().any(|r| r.in_between(*id)) {
@@ -64,7 +76,7 @@ impl Database {
         output
     }
 
-    fn merge_ranges(&mut self) -> bool {
+    pub fn merge_ranges(&mut self) -> bool {
         // first 2 usize are indexes to remove from ranges,
         // last Range type is the new type to push
         let mut merged_ranges_idx: Option<(usize, usize, Range)> = None;
@@ -85,7 +97,7 @@ impl Database {
         false
     }
 
-    fn part_two(&mut self) -> u64 {
+    pub fn part_two(&mut self) -> u64 {
         let mut output = 0u64;
         self.merge_ranges();
         for r in self.fresh_ranges.iter() {
@@ -122,19 +134,24 @@ fn get_column_width(operator_line: &str) -> Vec<u8> {
     widths
 }
 
-pub fn part_one() {
-    println!("Hello Day 5 - part 1!");
-    let input = super::load_input("input/input-day5");
-    let db = Database::from_input(&input);
-    println!("Output: {}", db.part_one());
-}
+pub struct Solver;
 
+impl Solution for Solver {
+    type Model = Database;
 
-pub fn part_two() {
-    println!("Hello Day 5 - part 2!");
-    let input = super::load_input("input/input-day5");
-    let mut db = Database::from_input(&input);
-    println!("Output: {}", db.part_two());
+    fn parse(input: &str) -> Result<Self::Model, ParseError> {
+        Database::from_input(input)
+    }
+
+    fn part_one(db: &Self::Model) -> Answer {
+        db.part_one().into()
+    }
+
+    fn part_two(db: &Self::Model) -> Answer {
+        // ranges are merged in place, work on a copy
+        let mut db = db.clone();
+        db.part_two().into()
+    }
 }
 
 #[cfg(test)]
@@ -143,12 +160,21 @@ mod tests {
 
     #[test]
     fn parse_database() {
-        let test_input = "";
-        let mut db = Database::from_input(test_input);
-        println!("Ranges: {:?}", db.fresh_ranges);
+        let test_input = "3-5
+10-14
+16-20
+12-18
+
+1
+5
+8
+11
+17
+32";
+        let mut db = Database::from_input(test_input).unwrap();
+        assert_eq!(db.part_one(), 3);
         db.merge_ranges();
-        println!("New Ranges: {:?}", db.fresh_ranges);
-        println!("Output: {}", db.part_two());
+        assert_eq!(db.part_two(), 14);
     }
 
 }
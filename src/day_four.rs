@@ -1,101 +1,219 @@
 #![allow(dead_code)]
 
-pub fn part_one() {
-    println!("Hello Day 4 - part 1!");
-    let input = super::load_input("input/input-day4");
-    let lines: Vec<&str> = input.trim().split("\n").collect();
-    let grid = Grid::from_lines(lines);
-    let output = grid.part_one();
-    println!("Final Output: {output}");
+use std::collections::VecDeque;
+use std::io::{self, Write};
+
+use crate::parse::{ParseError, parse_lines};
+use crate::solution::{Answer, Solution, TraceFormat};
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Model = Grid;
+
+    fn parse(input: &str) -> Result<Self::Model, ParseError> {
+        Grid::from_input(input)
+    }
+
+    fn part_one(grid: &Self::Model) -> Answer {
+        grid.part_one().into()
+    }
+
+    fn part_two(grid: &Self::Model) -> Answer {
+        // rolls are consumed while processing, work on a copy
+        let mut grid = grid.clone();
+        // grid.print();
+        grid.part_two().into()
+    }
+
+    // the wave removing each roll, in both removal orders
+    fn trace(grid: &Self::Model, format: TraceFormat, out: &mut dyn Write) -> io::Result<bool> {
+        if format == TraceFormat::Csv {
+            writeln!(out, "removal,x,y,wave")?;
+        }
+        for removal in [Removal::Sequential, Removal::Synchronous] {
+            let waves = grid.removal_waves(removal);
+            match format {
+                TraceFormat::Text | TraceFormat::Color => {
+                    writeln!(out, "{removal}: {} waves, {} rolls removed", waves.count(), waves.removed())?;
+                    writeln!(out, "{}", waves.heat_map())?;
+                }
+                TraceFormat::Csv => {
+                    for (n, wave) in waves.waves.iter().enumerate() {
+                        if let Some(wave) = wave {
+                            let (x, y) = (n as u32 % waves.size.0, n as u32 / waves.size.0);
+                            writeln!(out, "{removal},{x},{y},{wave}")?;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(true)
+    }
 }
 
-pub fn part_two() {
-    // println!("Hello Day 4 - part 2!");
-    let input = super::load_input("input/input-day4");
-    let lines: Vec<&str> = input.trim().split("\n").collect();
-    let mut grid = Grid::from_lines(lines);
-    let output = grid.part_two();
-    // grid.print();
-    println!("\nFinal Output: {output}");
+/// Cells around a roll that are looked at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighbourhood {
+    /// | 0 | 1 | 2 |
+    /// | 3 | x | 4 |
+    /// | 5 | 6 | 7 |
+    Moore,
+    /// |   | 0 |   |
+    /// | 1 | x | 2 |
+    /// |   | 3 |   |
+    VonNeumann,
+    /// the square of cells at most r steps away, Moore for r = 1
+    Radius(u32),
+}
+
+impl Neighbourhood {
+    // steps from a cell to its neighbours
+    fn offsets(&self) -> Vec<(i64, i64)> {
+        let r = match self {
+            Neighbourhood::Moore => 1,
+            Neighbourhood::VonNeumann => return vec![(0, -1), (-1, 0), (1, 0), (0, 1)],
+            Neighbourhood::Radius(r) => *r as i64,
+        };
+        (-r..=r).flat_map(|dy| (-r..=r).map(move |dx| (dx, dy))).filter(|d| *d != (0, 0)).collect()
+    }
+}
+
+/// What lies past the edges of the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Boundary {
+    /// walls, counted as neighbours
+    Walls,
+    /// nothing, as in the puzzle
+    #[default]
+    Open,
+    /// the grid wraps around, left to right and top to bottom
+    Toroidal,
+}
+
+/// When a roll is accessible: it has fewer than `threshold` rolls
+/// in its neighbourhood.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rule {
+    pub threshold: u32,
+    pub neighbourhood: Neighbourhood,
+    pub boundary: Boundary,
+}
+
+impl Default for Rule {
+    // fewer than 4 of the 8 neighbours
+    fn default() -> Self {
+        Self { threshold: 4, neighbourhood: Neighbourhood::Moore, boundary: Boundary::Open }
+    }
 }
 
-struct Grid {
+#[derive(Clone)]
+pub struct Grid {
     size: (u32, u32),
-    values: Vec<bool>
+    values: Vec<bool>,
+    rule: Rule,
+    // the neighbourhood of the rule
+    offsets: Vec<(i64, i64)>,
 }
 
 impl Grid {
-    fn from_lines(lines: Vec<&str>) -> Self {
+    pub fn from_input(input: &str) -> Result<Self, ParseError> {
+        let rows: Vec<Vec<bool>> = parse_lines(input, |line| {
+            line.trim_end()
+                .char_indices()
+                .map(|(n, c)| match c {
+                    '@' => Ok(true),
+                    '.' => Ok(false),
+                    _ => Err(ParseError::at(line, &line[n..n + c.len_utf8()], format!("'{c}' is neither a roll (@) nor empty (.)"))),
+                })
+                .collect()
+        })?;
+        let Some(first_row) = rows.first() else {
+            return Err(ParseError::at(input, input, "the grid is empty"));
+        };
         let size = (
-            lines.first().unwrap().len() as u32,
-            lines.len() as u32
+            first_row.len() as u32,
+            rows.len() as u32
         );
-        #[allow(unused_assignments)]
-        let mut values: Vec<bool> = Vec::with_capacity((size.0 * size.1) as usize); 
-        values = lines
-            .iter()
-            .flat_map(|l| l.chars())
-            .collect::<Vec<char>>()
-            .iter()
-            .map(
-                |c| if *c == '@' {
-                    true
-                } else {
-                    false
-                })
-            .collect();
+        // every row must be as wide as the first one
+        if let Some(n) = rows.iter().position(|r| r.len() != first_row.len()) {
+            let line = input.lines().filter(|l| !l.trim().is_empty()).nth(n).unwrap_or(input);
+            return Err(ParseError::at(input, line, format!("row is {} wide, expected {}", rows[n].len(), size.0)));
+        }
+        let values: Vec<bool> = rows.into_iter().flatten().collect();
         // println!("{values:?}");
-        Self { size, values }
-    }
-
-    // | 0 | 1 | 2 |
-    // | 3 | x | 4 |
-    // | 5 | 6 | 7 |
-    fn get_neighbour_count(&self, pos: (u32, u32)) -> u8 {
-        let mut counter = 0;
-        let from = (pos.0.saturating_sub(1), pos.1.saturating_sub(1));
-        let to = ((pos.0 + 1).clamp(0, self.size.0 - 1), (pos.1 + 1).clamp(0, self.size.1 - 1) );
-        for y in from.1..=to.1 {
-            for x in from.0..=to.0 {
-                // skip current position
-                if x == pos.0 && y == pos.1 {
-                    continue
-                }
-                if let Some(v) = self.values.get((x + self.size.0 * y) as usize) {
-                    if *v {
-                        counter += 1;
-                    }
-                }
+        Ok(Self::new(size, values))
+    }
+
+    fn new(size: (u32, u32), values: Vec<bool>) -> Self {
+        let rule = Rule::default();
+        Self { size, values, rule, offsets: rule.neighbourhood.offsets() }
+    }
+
+    /// The same grid, its rolls accessible under another rule.
+    pub fn with_rule(self, rule: Rule) -> Self {
+        Self { offsets: rule.neighbourhood.offsets(), rule, ..self }
+    }
+
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    pub fn get_neighbour_count(&self, pos: (u32, u32)) -> u32 {
+        self.count_around(&self.values, (pos.0 + self.size.0 * pos.1) as usize)
+    }
+
+    // rolls (and walls) around the n-th cell, when the grid holds `values`
+    fn count_around(&self, values: &[bool], n: usize) -> u32 {
+        self.walls(n) + self.neighbours(n).filter(|m| values[*m]).count() as u32
+    }
+
+    fn accessible(&self, n: usize) -> bool {
+        self.values[n] && self.count_around(&self.values, n) < self.rule.threshold
+    }
+
+    // indices of the cells around the n-th one, in the grid. A cell can be
+    // reached twice, or be its own neighbour, when a small grid wraps around
+    fn neighbours(&self, n: usize) -> impl Iterator<Item = usize> + '_ {
+        let (width, height) = (self.size.0 as i64, self.size.1 as i64);
+        let (x, y) = (n as i64 % width, n as i64 / width);
+        let toroidal = self.rule.boundary == Boundary::Toroidal;
+        self.offsets.iter().filter_map(move |(dx, dy)| {
+            let (mut nx, mut ny) = (x + dx, y + dy);
+            if toroidal {
+                (nx, ny) = (nx.rem_euclid(width), ny.rem_euclid(height));
             }
+            ((0..width).contains(&nx) && (0..height).contains(&ny)).then_some((nx + width * ny) as usize)
+        })
+    }
+
+    // neighbours of the n-th cell past the edges, when they are walls
+    fn walls(&self, n: usize) -> u32 {
+        match self.rule.boundary {
+            Boundary::Walls => (self.offsets.len() - self.neighbours(n).count()) as u32,
+            Boundary::Open | Boundary::Toroidal => 0,
         }
-        counter
     }
 
-    fn part_one(&self) -> u32 {
+    pub fn part_one(&self) -> u32 {
         let mut output = 0;
-        for (n,v) in self.values.iter().enumerate() {
-            if *v {
-                let (x, y) = (n as u32 % self.size.0, n as u32 / self.size.0);
-                let nb_counter = self.get_neighbour_count((x,y));
-                if nb_counter < 4 {
-                    output += 1;
-                } 
+        for n in 0..self.values.len() {
+            if self.accessible(n) {
+                output += 1;
             }
         }
         output
     }
 
-    fn print(&self) {
+    pub fn print(&self) {
 
         let mut visual = String::new();
         for (n,v) in self.values.iter().enumerate() {
-            if n != 0 && n as u32 % self.size.0 == 0 { 
+            if n != 0 && (n as u32).is_multiple_of(self.size.0) { 
                 visual = format!("{visual}\n");
             }
             if *v {
-                let (x, y) = (n as u32 % self.size.0, n as u32 / self.size.0);
-                let nb_counter = self.get_neighbour_count((x,y));
-                if nb_counter < 4 {
+                if self.accessible(n) {
                     visual = format!("{visual} x");
                 } else {
                     visual = format!("{visual} @");
@@ -107,47 +225,277 @@ impl Grid {
         println!("{visual}");
     }
 
-    fn process(&mut self) -> u32 {
+    pub fn process(&mut self) -> u32 {
         // same as part one, but consume the values (true -> false)
         let mut output = 0;
         for n in 0..self.values.len() {
-            if self.values[n] {
-                let (x, y) = (n as u32 % self.size.0, n as u32 / self.size.0);
-                let nb_counter = self.get_neighbour_count((x,y));
-                if nb_counter < 4 {
-                    self.values[n] = false;
-                    output += 1;
-                } 
+            if self.accessible(n) {
+                self.values[n] = false;
+                output += 1;
             }
         }
         output
     }
 
-    fn part_two(&mut self) -> u32{
+    pub fn part_two(&mut self) -> u32 {
+        self.peel()
+    }
+
+    /// The wave removing each roll, the grid is left untouched.
+    pub fn removal_waves(&self, removal: Removal) -> Waves {
+        let mut waves = vec![None; self.values.len()];
+        match removal {
+            Removal::Sequential => {
+                let mut grid = self.clone();
+                let mut wave = 1;
+                loop {
+                    let before = grid.values.clone();
+                    if grid.process() == 0 {
+                        break;
+                    }
+                    for (n, (was, is)) in before.iter().zip(&grid.values).enumerate() {
+                        if *was && !*is {
+                            waves[n] = Some(wave);
+                        }
+                    }
+                    wave += 1;
+                }
+            }
+            // peeling wave by wave: a roll joins the next wave
+            // when the current one makes it accessible
+            Removal::Synchronous => {
+                let threshold = self.rule.threshold;
+                let mut values = self.values.clone();
+                let mut counts: Vec<u32> = (0..values.len()).map(|n| self.count_around(&values, n)).collect();
+                let mut current: Vec<usize> = (0..values.len()).filter(|n| values[*n] && counts[*n] < threshold).collect();
+                let mut wave = 1;
+                while !current.is_empty() {
+                    current.iter().for_each(|n| values[*n] = false);
+                    let mut next = vec![];
+                    for &n in current.iter() {
+                        waves[n] = Some(wave);
+                        for m in self.neighbours(n) {
+                            if values[m] {
+                                counts[m] -= 1;
+                                if counts[m] + 1 == threshold {
+                                    next.push(m);
+                                }
+                            }
+                        }
+                    }
+                    current = next;
+                    wave += 1;
+                }
+            }
+        }
+        Waves { size: self.size, rolls: self.values.clone(), waves }
+    }
+
+    /// Remove every accessible roll until none is left, returns the count.
+    ///
+    /// The neighbour count of each roll is kept up to date: a removed roll
+    /// only lowers its neighbours' counts, and a roll is queued once, when
+    /// it becomes accessible. The work is linear in the grid size.
+    pub fn peel(&mut self) -> u32 {
+        let threshold = self.rule.threshold;
+        // the neighbours are borrowed from the grid while its rolls are removed
+        let mut values = std::mem::take(&mut self.values);
+        let mut counts: Vec<u32> = (0..values.len()).map(|n| self.count_around(&values, n)).collect();
+        let mut queue: VecDeque<usize> = (0..values.len()).filter(|n| values[*n] && counts[*n] < threshold).collect();
         let mut output = 0;
-        loop {
-           let pass = self.process();
-           if pass != 0 {
-               output += pass;
-           } else {
-               break
-           }
+        while let Some(n) = queue.pop_front() {
+            values[n] = false;
+            output += 1;
+            for m in self.neighbours(n) {
+                if values[m] {
+                    counts[m] -= 1;
+                    // the count just went below the threshold: queued exactly once
+                    if counts[m] + 1 == threshold {
+                        queue.push_back(m);
+                    }
+                }
+            }
         }
+        self.values = values;
         output
     }
 }
 
+/// How the accessible rolls are removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Removal {
+    /// one pass over the grid in reading order, a removed roll frees
+    /// the rolls after it in the same pass, as `Grid::process` does
+    Sequential,
+    /// every roll accessible at the start of a wave is removed,
+    /// the removals of a wave only count for the next one
+    Synchronous,
+}
+
+impl std::fmt::Display for Removal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Removal::Sequential => write!(f, "sequential"),
+            Removal::Synchronous => write!(f, "synchronous"),
+        }
+    }
+}
+
+/// The wave in which each roll of a grid is removed, from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Waves {
+    size: (u32, u32),
+    rolls: Vec<bool>,
+    /// none for the empty cells and the rolls never removed
+    waves: Vec<Option<u32>>,
+}
+
+impl Waves {
+    pub fn wave(&self, pos: (u32, u32)) -> Option<u32> {
+        self.waves[(pos.0 + self.size.0 * pos.1) as usize]
+    }
+
+    /// Number of waves until no roll is accessible.
+    pub fn count(&self) -> u32 {
+        self.waves.iter().flatten().copied().max().unwrap_or(0)
+    }
+
+    pub fn removed(&self) -> u32 {
+        self.waves.iter().flatten().count() as u32
+    }
+
+    /// Rolls removed by each wave.
+    pub fn sizes(&self) -> Vec<u32> {
+        let mut sizes = vec![0; self.count() as usize];
+        self.waves.iter().flatten().for_each(|w| sizes[*w as usize - 1] += 1);
+        sizes
+    }
+
+    /// The grid with the wave of each removed roll: 1 to 9, then a to z,
+    /// `+` past 35 waves. Rolls left are `@` and empty cells `.`.
+    pub fn heat_map(&self) -> String {
+        self.render(|roll, wave| match (roll, wave) {
+            (_, Some(wave)) => char::from_digit(wave, 36).unwrap_or('+'),
+            (true, None) => '@',
+            (false, None) => '.',
+        })
+    }
+
+    /// The grid before the first wave, then after each one:
+    /// the rolls removed by the wave are `x`.
+    pub fn frames(&self) -> Vec<String> {
+        (0..=self.count())
+            .map(|frame| {
+                self.render(|roll, wave| match wave {
+                    Some(wave) if wave == frame => 'x',
+                    Some(wave) if wave < frame => '.',
+                    _ if roll => '@',
+                    _ => '.',
+                })
+            })
+            .collect()
+    }
+
+    fn render(&self, cell: impl Fn(bool, Option<u32>) -> char) -> String {
+        let mut visual = String::with_capacity(self.waves.len() + self.size.1 as usize);
+        for (n, (roll, wave)) in self.rolls.iter().zip(&self.waves).enumerate() {
+            if n != 0 && (n as u32).is_multiple_of(self.size.0) {
+                visual.push('\n');
+            }
+            visual.push(cell(*roll, *wave));
+        }
+        visual
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::XorShift;
 
     #[test]
     fn grid() {
-        let test_input = "";
-        let lines: Vec<&str> = test_input.trim().split("\n").collect();
-        let mut grid = Grid::from_lines(lines);
-        let output = grid.part_two();
-        println!("{output}");
+        let test_input = "..@@.@@@@.
+@@@.@.@.@@
+@@@@@.@.@@
+@.@@@@..@.
+@@.@@@@.@@
+.@@@@@@@.@
+.@.@.@.@@@
+@.@@@.@@@@
+.@@@@@@@@.
+@.@.@@@.@.";
+        let grid = Solver::parse(test_input).unwrap();
+        assert_eq!(Solver::part_one(&grid), Answer::Number(13));
+        assert_eq!(Solver::part_two(&grid), Answer::Number(43));
+        let waves = grid.removal_waves(Removal::Synchronous);
+        assert_eq!(waves.sizes(), vec![13, 12, 7, 5, 2, 1, 1, 1, 1]);
+        assert_eq!(grid.removal_waves(Removal::Sequential).removed(), 43);
+    }
+
+    #[test]
+    fn removal_waves() {
+        let grid = Grid::from_input("@@@@\n@@@@\n.@@.\n").unwrap();
+        let waves = grid.removal_waves(Removal::Synchronous);
+        assert_eq!(waves.heat_map(), "1331\n2442\n.33.");
+        assert_eq!(waves.sizes(), vec![2, 2, 4, 2]);
+        assert_eq!(waves.frames()[1], "x@@x\n@@@@\n.@@.");
+        assert_eq!(waves.frames()[4], "....\n.xx.\n....");
+        // the rolls freed in a pass are removed in the same pass
+        let waves = grid.removal_waves(Removal::Sequential);
+        assert_eq!(waves.heat_map(), "1221\n1221\n.11.");
+        assert_eq!(waves.wave((1, 1)), Some(2));
+    }
+
+    #[test]
+    fn rules() {
+        let grid = Grid::from_input("@@@\n@@@\n@@@\n").unwrap();
+        assert_eq!(grid.rule(), Rule::default());
+        assert_eq!(grid.part_one(), 4);
+        let rule = |threshold, neighbourhood, boundary| Rule { threshold, neighbourhood, boundary };
+        let accessible = |rule| grid.clone().with_rule(rule).part_one();
+        // only the corners have 2 neighbours
+        assert_eq!(accessible(rule(3, Neighbourhood::VonNeumann, Boundary::Open)), 4);
+        // a corner has 3 rolls and 5 walls around it
+        assert_eq!(accessible(rule(4, Neighbourhood::Moore, Boundary::Walls)), 0);
+        assert_eq!(accessible(rule(9, Neighbourhood::Moore, Boundary::Walls)), 9);
+        // every roll sees the 8 others
+        assert_eq!(accessible(rule(8, Neighbourhood::Moore, Boundary::Toroidal)), 0);
+        assert_eq!(accessible(rule(8, Neighbourhood::Radius(2), Boundary::Open)), 0);
+        assert_eq!(accessible(rule(9, Neighbourhood::Radius(2), Boundary::Open)), 9);
+        let mut grid = grid.with_rule(rule(4, Neighbourhood::Moore, Boundary::Toroidal));
+        assert_eq!(grid.get_neighbour_count((0, 0)), 8);
+        assert_eq!(grid.peel(), 0);
+    }
+
+    #[test]
+    fn peel_matches_passes() {
+        let mut random = XorShift::new(0x2025_0004);
+        for _ in 0..200 {
+            let (width, height) = (random.below(20) + 1, random.below(20) + 1);
+            let input: String = (0..height)
+                .map(|_| (0..width).map(|_| if random.below(4) == 0 { '.' } else { '@' }).collect::<String>() + "\n")
+                .collect();
+            let rule = Rule {
+                threshold: random.below(10) as u32,
+                neighbourhood: [Neighbourhood::Moore, Neighbourhood::VonNeumann, Neighbourhood::Radius(random.below(3) as u32)]
+                    [random.below(3) as usize],
+                boundary: [Boundary::Walls, Boundary::Open, Boundary::Toroidal][random.below(3) as usize],
+            };
+            let mut peeled = Grid::from_input(&input).unwrap().with_rule(rule);
+            let mut passes = peeled.clone();
+            let mut removed = 0;
+            loop {
+                match passes.process() {
+                    0 => break,
+                    pass => removed += pass,
+                }
+            }
+            let waves = peeled.removal_waves(Removal::Synchronous);
+            assert_eq!(peeled.peel(), removed, "{input}{rule:?}");
+            assert_eq!(peeled.values, passes.values, "{input}{rule:?}");
+            assert_eq!(waves.removed(), removed, "{input}{rule:?}");
+        }
     }
 
 }
@@ -2,37 +2,46 @@
 
 use std::fmt::Display;
 
+use crate::parse::{ParseError, parse_lines};
+use crate::solution::{Answer, Solution};
+
 #[derive(Clone, Eq, PartialEq, Debug)]
-enum Color {
+pub enum Color {
     Red,
     Green,
 }
 
 #[derive(Clone, Debug)]
-struct Tile {
-    x: u32,
-    y: u32,
-    color: Color,
+pub struct Tile {
+    pub x: u32,
+    pub y: u32,
+    pub color: Color,
 }
 
 impl Tile {
-    fn from_str(input: &str, color: Color) -> Self {
-        let (x, y) = input.split_once(",").expect("Wrong line format");
-        let x = x.parse().expect("X is not a number");
-        let y = y.parse().expect("Y is not a number");
-        Self { x, y, color }
+    pub fn from_str(input: &str, color: Color) -> Result<Self, ParseError> {
+        let (x, y) = input
+            .trim_end()
+            .split_once(",")
+            .ok_or_else(|| ParseError::at(input, input, "expected the X,Y coordinates"))?;
+        let coordinate = |value: &str, name: &str| {
+            value
+                .parse::<u32>()
+                .map_err(|e| ParseError::at(input, value, format!("{name} '{value}' is not a number: {e}")))
+        };
+        Ok(Self { x: coordinate(x, "X")?, y: coordinate(y, "Y")?, color })
     }
 }
 
 // size stored only for display
-struct Floor {
+pub struct Floor {
     width: u32, 
     height: u32,
     tiles: Vec<Tile>,
 }
 
 impl Floor {
-    fn from_red_tiles(red_tiles: Vec<Tile>) -> Self {
+    pub fn from_red_tiles(red_tiles: Vec<Tile>) -> Self {
         let width = red_tiles.iter().max_by_key(|t| t.x).unwrap().x;
         let height = red_tiles.iter().max_by_key(|t| t.y).unwrap().y;
         let mut tiles: Vec<Tile> = vec![];
@@ -80,12 +89,12 @@ impl Floor {
         let max_x = std::cmp::max(tile_a.x, tile_b.x);
         let min_y = std::cmp::min(tile_a.y, tile_b.y);
         let max_y = std::cmp::max(tile_a.y, tile_b.y);
-        return self.tiles.iter().any(|t| {
+        self.tiles.iter().any(|t| {
             t.color == Color::Green && t.x > min_x && t.x < max_x && t.y > min_y && t.y < max_y
-        });
+        })
     }
 
-    fn largest_area(&self) -> u64 {
+    pub fn largest_area(&self) -> u64 {
         let mut areas: Vec<(u64, &Tile, &Tile)> = vec![];
         let red_tiles = self
             .tiles
@@ -94,7 +103,7 @@ impl Floor {
             .collect::<Vec<&Tile>>();
         for (n, tile_a) in red_tiles.iter().enumerate() {
             for (_, tile_b) in red_tiles.iter().enumerate().filter(|(i, _)| *i > n) {
-                areas.push((calc_area(tile_a, tile_b), &tile_a, &tile_b));
+                areas.push((calc_area(tile_a, tile_b), tile_a, tile_b));
             }
         }
         areas.sort_by_key(|k| k.0);
@@ -129,47 +138,22 @@ impl Display for Floor {
         write!(f, "{}", lines)
     }
 }
-fn parse_red_tiles(mode: super::InputMode) -> Vec<Tile> {
-    let input: String;
-    match mode {
-        crate::InputMode::Example => {
-            input = "1,5
-3,5
-3,8
-7,8
-7,5
-9,5
-9,10
-11,10
-11,3
-6,3
-6,7
-4,7
-4,1
-13,1
-13,12
-1,12"
-            .to_string();
-        }
-        crate::InputMode::Normal => {
-            input = super::load_input("input/input-day9");
-        }
+pub fn parse_red_tiles(input: &str) -> Result<Vec<Tile>, ParseError> {
+    let tiles = parse_lines(input, |l| Tile::from_str(l, Color::Red))?;
+    if tiles.len() < 2 {
+        return Err(ParseError::at(input, &input[input.len()..], "at least two red tiles are needed"));
     }
-    let lines: Vec<&str> = input.lines().collect();
-    lines
-        .into_iter()
-        .map(|l| Tile::from_str(l, Color::Red))
-        .collect()
+    Ok(tiles)
 }
 
-fn calc_area(a: &Tile, b: &Tile) -> u64 {
+pub fn calc_area(a: &Tile, b: &Tile) -> u64 {
     let l = (a.x as i64 - b.x as i64).abs() + 1;
     let w = (a.y as i64 - b.y as i64).abs() + 1;
     (l * w) as u64
 }
 
-pub fn part_one() {
-    let tiles = parse_red_tiles(super::InputMode::Normal);
+// largest rectangle with two red tiles on opposite corners
+pub fn largest_red_area(tiles: &[Tile]) -> u64 {
     let mut areas: Vec<u64> = vec![];
     for (n, tile_a) in tiles.iter().enumerate() {
         for (_, tile_b) in tiles.iter().enumerate().filter(|(i, _)| *i > n) {
@@ -177,14 +161,68 @@ pub fn part_one() {
         }
     }
     areas.sort();
-    let max_area = areas.last().unwrap();
-    println!("Max Area: {max_area}");
+    *areas.last().unwrap()
 }
 
-pub fn part_two() {
-    let red_tiles = parse_red_tiles(super::InputMode::Example);
-    let floor = Floor::from_red_tiles(red_tiles);
-    // println!("{floor}");
-    let largest_area = floor.largest_area();
-    println!("Largest area: {largest_area}");
+pub struct Solver;
+
+impl Solution for Solver {
+    type Model = Vec<Tile>;
+
+    fn parse(input: &str) -> Result<Self::Model, ParseError> {
+        parse_red_tiles(input)
+    }
+
+    fn part_one(tiles: &Self::Model) -> Answer {
+        largest_red_area(tiles).into()
+    }
+
+    fn part_two(red_tiles: &Self::Model) -> Answer {
+        let floor = Floor::from_red_tiles(red_tiles.clone());
+        // println!("{floor}");
+        floor.largest_area().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn floor() {
+        let test_input = "1,5
+3,5
+3,8
+7,8
+7,5
+9,5
+9,10
+11,10
+11,3
+6,3
+6,7
+4,7
+4,1
+13,1
+13,12
+1,12";
+        let red_tiles = Solver::parse(test_input).unwrap();
+        // from corner to corner
+        assert_eq!(Solver::part_one(&red_tiles), Answer::Number(13 * 12));
+    }
+
+    #[test]
+    fn example() {
+        let test_input = "7,1
+11,1
+11,7
+9,7
+9,5
+2,5
+2,3
+7,3";
+        let red_tiles = Solver::parse(test_input).unwrap();
+        assert_eq!(Solver::part_one(&red_tiles), Answer::Number(50));
+        assert_eq!(Solver::part_two(&red_tiles), Answer::Number(24));
+    }
 }
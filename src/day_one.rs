@@ -1,65 +1,492 @@
 #![allow(dead_code)]
 
-use std::{fs::File, io::Read, io::{Write, BufWriter}};
-
-pub fn day_one() {
-    let output_file = File::create("asset/output").unwrap();
-    let mut output_writer = BufWriter::new(&output_file);
-    let mut input_file = File::open("asset/input").unwrap();
-    let mut input_data = String::new();
-    input_file.read_to_string(&mut input_data).unwrap();
-    let lines: Vec<&str> = input_data.split_terminator("\n").collect();
-    let mut number: isize = 50;
-    let mut zero_occurrences = 0;
-
-    let _ = writeln!(output_writer, "Starting position: {number}");
-    for line in lines.iter() {
-        let old_number = number;
+use std::io::{self, Write};
+
+use crate::parse::ParseError;
+use crate::solution::{Answer, Solution, TraceFormat};
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Model = Safe;
+
+    fn parse(input: &str) -> Result<Self::Model, ParseError> {
+        Safe::from_input(input)
+    }
+
+    fn part_one(safe: &Self::Model) -> Answer {
+        zero_counts_answer(safe, |count| count.landings)
+    }
+
+    fn part_two(safe: &Self::Model) -> Answer {
+        zero_counts_answer(safe, |count| count.passes)
+    }
+
+    fn trace(safe: &Self::Model, format: TraceFormat, out: &mut dyn Write) -> io::Result<bool> {
+        write_trace(&safe.trace(), format, out)?;
+        Ok(true)
+    }
+}
+
+// a single dial gives a number, several dials give
+// the count of each one as `A=1,B=3,default=2`, no rotation at all gives 0
+fn zero_counts_answer(safe: &Safe, count: impl Fn(&ZeroCount) -> u64) -> Answer {
+    let counts = safe.zero_counts();
+    match counts.as_slice() {
+        [] => 0u64.into(),
+        [single] => count(single).into(),
+        _ => counts
+            .iter()
+            .map(|c| {
+                let name = if c.name.is_empty() { "default" } else { &c.name };
+                format!("{name}={}", count(c))
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+            .into(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Rotation {
+    /// index of the rotated dial in the safe
+    pub dial: usize,
+    pub direction: Direction,
+    pub clicks: isize,
+}
+
+impl Rotation {
+    pub fn from_line(line: &str) -> Result<Self, ParseError> {
         //split the line
-        let (dir, value) = line.split_at(1);
-        // convert str to usize
-        let mut value_n = value.parse::<isize>().unwrap();
-        // if there is no rest, simply add zero occurrencies by division
-        if value_n % 100 == 0 {
-            zero_occurrences += value_n / 100;
-            continue;
+        let dir_len = line.chars().next().map_or(0, char::len_utf8);
+        let (dir, value) = line.split_at(dir_len);
+        let direction = match dir {
+            "R" => Direction::Right,
+            "L" => Direction::Left,
+            _ => return Err(ParseError::at(line, dir, format!("wrong direction '{dir}', expected L or R"))),
+        };
+        // convert str to isize
+        let clicks = value
+            .parse::<isize>()
+            .ok()
+            .filter(|c| *c >= 0)
+            .ok_or_else(|| ParseError::at(line, value, format!("'{value}' is not a number of clicks")))?;
+        Ok(Self { dial: 0, direction, clicks })
+    }
+}
+
+/// The safe dial: `size` positions numbered from 0,
+/// the pointer is on `start` before the first rotation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dial {
+    size: isize,
+    start: isize,
+}
+
+impl Default for Dial {
+    fn default() -> Self {
+        Self { size: 100, start: 50 }
+    }
+}
+
+impl Dial {
+    pub fn new(size: isize, start: isize) -> Self {
+        assert!(size > 0, "a dial needs at least one position");
+        assert!((0..size).contains(&start), "start {start} is not on a dial of size {size}");
+        Self { size, start }
+    }
+
+    pub fn size(&self) -> isize {
+        self.size
+    }
+
+    pub fn start(&self) -> isize {
+        self.start
+    }
+
+    // position of the pointer after a rotation from `position`
+    pub fn rotate(&self, position: isize, rotation: &Rotation) -> isize {
+        let clicks = match rotation.direction {
+            Direction::Right => rotation.clicks,
+            Direction::Left => -rotation.clicks,
+        };
+        // reduce first, a full turn of clicks could overflow
+        (position + clicks.rem_euclid(self.size)).rem_euclid(self.size)
+    }
+
+    /// Count the rotations ending with the pointer on zero.
+    pub fn zero_landings(&self, rotations: &[Rotation]) -> u64 {
+        let mut number = self.start;
+        let mut zero_occurrences = 0;
+        for rotation in rotations.iter() {
+            number = self.rotate(number, rotation);
+            if number == 0 {
+                zero_occurrences += 1;
+            }
         }
-        // if value is more than 100, add one occurrency and calculate the rest
-        if value_n > 100 {
-            zero_occurrences += value_n / 100;
-            value_n = value_n % 100;
+        zero_occurrences
+    }
+
+    /// Count the clicks landing on zero during a rotation from `position`,
+    /// the last click included.
+    pub fn zero_crossings(&self, position: isize, rotation: &Rotation) -> u64 {
+        // clicks needed to reach zero the first time
+        let to_zero = match rotation.direction {
+            Direction::Right => self.size - position,
+            Direction::Left if position == 0 => self.size,
+            Direction::Left => position,
+        };
+        if rotation.clicks < to_zero {
+            0
+        } else {
+            ((rotation.clicks - to_zero) / self.size + 1) as u64
         }
-        
-        match dir {
-            "R" => {
-                number += value_n;
-                if number == 100 {
-                    zero_occurrences += 1;
-                    number = 0;
-                }
-                if number > 100 {
-                    zero_occurrences += 1;
-                    number = number % 100;
-                }
-            },
-            "L" => {
-                number -= value_n;
+    }
+
+    /// Count every click landing on zero, during a rotation or at its end.
+    pub fn zero_passes(&self, rotations: &[Rotation]) -> u64 {
+        let mut number = self.start;
+        let mut zero_occurrences = 0;
+        for rotation in rotations.iter() {
+            zero_occurrences += self.zero_crossings(number, rotation);
+            number = self.rotate(number, rotation);
+        }
+        zero_occurrences
+    }
+
+    /// Same as [`Dial::zero_passes`], turning the dial one click at a time:
+    /// slow, but obviously right.
+    pub fn zero_passes_by_click(&self, rotations: &[Rotation]) -> u64 {
+        let mut number = self.start;
+        let mut zero_occurrences = 0;
+        for rotation in rotations.iter() {
+            let step = match rotation.direction {
+                Direction::Right => 1,
+                Direction::Left => -1,
+            };
+            for _ in 0..rotation.clicks {
+                number = (number + step).rem_euclid(self.size);
                 if number == 0 {
                     zero_occurrences += 1;
                 }
-                if number < 0 {
-                    // doesnt count 2 times
-                    if old_number !=0 {
-                        zero_occurrences += 1;
-                    }
-                    number = 100 + number;
+            }
+        }
+        zero_occurrences
+    }
+}
+
+/// A dial of the safe with its name, the default dial has an empty name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamedDial {
+    pub name: String,
+    pub dial: Dial,
+}
+
+/// The zeros reached by a dial.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZeroCount {
+    pub name: String,
+    pub landings: u64,
+    pub passes: u64,
+}
+
+/// The dials of the safe and the rotations applied to them, in order.
+///
+/// The input is a list of rotations like `L68` for the default dial
+/// (size 100, starting on 50). More dials are declared by a line
+/// `dial <name> <size> [<start>]`, the start defaults to half the size,
+/// and rotated by prefixing the instruction with their name: `A:R30`.
+#[derive(Debug, Clone)]
+pub struct Safe {
+    pub dials: Vec<NamedDial>,
+    pub rotations: Vec<Rotation>,
+}
+
+impl Safe {
+    pub fn from_input(input: &str) -> Result<Self, ParseError> {
+        let mut safe = Self { dials: vec![], rotations: vec![] };
+        for line in input.lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            safe.add_line(line).map_err(|e| e.within(input, line))?;
+        }
+        Ok(safe)
+    }
+
+    fn add_line(&mut self, line: &str) -> Result<(), ParseError> {
+        if let Some(declaration) = line.strip_prefix("dial ") {
+            return self.declare(line, declaration);
+        }
+        let (name, instruction) = match line.split_once(':') {
+            Some((name, instruction)) => (name, instruction),
+            None => ("", line),
+        };
+        let dial = match self.dials.iter().position(|d| d.name == name) {
+            Some(dial) => dial,
+            // the default dial exists as soon as it is used
+            None if name.is_empty() => {
+                self.dials.push(NamedDial { name: String::new(), dial: Dial::default() });
+                self.dials.len() - 1
+            }
+            None => return Err(ParseError::at(line, name, format!("dial '{name}' is not declared"))),
+        };
+        let rotation = Rotation::from_line(instruction).map_err(|e| e.within(line, instruction))?;
+        self.rotations.push(Rotation { dial, ..rotation });
+        Ok(())
+    }
+
+    fn declare(&mut self, line: &str, declaration: &str) -> Result<(), ParseError> {
+        let fields: Vec<&str> = declaration.split_whitespace().collect();
+        let (name, size, start) = match fields.as_slice() {
+            [name, size] => (*name, *size, None),
+            [name, size, start] => (*name, *size, Some(*start)),
+            _ => return Err(ParseError::at(line, line, "expected dial <name> <size> [<start>]")),
+        };
+        if !name.chars().all(char::is_alphanumeric) {
+            return Err(ParseError::at(line, name, format!("'{name}' is not a dial name, use letters and digits")));
+        }
+        if self.dials.iter().any(|d| d.name == name) {
+            return Err(ParseError::at(line, name, format!("dial '{name}' is declared twice")));
+        }
+        let size: isize = size
+            .parse()
+            .ok()
+            .filter(|s| *s > 0)
+            .ok_or_else(|| ParseError::at(line, size, format!("'{size}' is not a dial size")))?;
+        let start: isize = match start {
+            Some(start) => start
+                .parse()
+                .ok()
+                .filter(|s| (0..size).contains(s))
+                .ok_or_else(|| ParseError::at(line, start, format!("'{start}' is not a position of a dial of size {size}")))?,
+            None => size / 2,
+        };
+        self.dials.push(NamedDial { name: name.to_string(), dial: Dial::new(size, start) });
+        Ok(())
+    }
+
+    // rotations of a single dial
+    fn rotations_of(&self, dial: usize) -> Vec<Rotation> {
+        self.rotations.iter().filter(|r| r.dial == dial).copied().collect()
+    }
+
+    /// Landings on zero and zero passes of every dial.
+    pub fn zero_counts(&self) -> Vec<ZeroCount> {
+        self.dials
+            .iter()
+            .enumerate()
+            .map(|(n, named)| {
+                let rotations = self.rotations_of(n);
+                ZeroCount {
+                    name: named.name.clone(),
+                    landings: named.dial.zero_landings(&rotations),
+                    passes: named.dial.zero_passes(&rotations),
                 }
-            },
-            //wrong direction, should not be present in input
-            _ => panic!(),
+            })
+            .collect()
+    }
+
+    /// The pointer moves of every rotation, with the zero passes
+    /// of the rotated dial so far.
+    pub fn trace(&self) -> Vec<TraceStep> {
+        let mut numbers: Vec<isize> = self.dials.iter().map(|d| d.dial.start()).collect();
+        let mut zero_occurrences = vec![0; self.dials.len()];
+        let mut steps = vec![];
+        for (n, rotation) in self.rotations.iter().enumerate() {
+            let dial = &self.dials[rotation.dial].dial;
+            let from = numbers[rotation.dial];
+            zero_occurrences[rotation.dial] += dial.zero_crossings(from, rotation);
+            numbers[rotation.dial] = dial.rotate(from, rotation);
+            steps.push(TraceStep {
+                step: n + 1,
+                dial: self.dials[rotation.dial].name.clone(),
+                direction: rotation.direction,
+                clicks: rotation.clicks,
+                from,
+                to: numbers[rotation.dial],
+                zeros: zero_occurrences[rotation.dial],
+            });
+        }
+        steps
+    }
+}
+
+/// A rotation of the dial, as written in a trace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceStep {
+    pub step: usize,
+    /// empty for the default dial
+    pub dial: String,
+    pub direction: Direction,
+    pub clicks: isize,
+    pub from: isize,
+    pub to: isize,
+    /// zero passes from the first rotation up to this one
+    pub zeros: u64,
+}
+
+// the dial column is there only when named dials are used
+pub fn write_trace(steps: &[TraceStep], format: TraceFormat, out: &mut dyn Write) -> io::Result<()> {
+    let named = steps.iter().any(|s| !s.dial.is_empty());
+    if format == TraceFormat::Csv {
+        let dial = if named { "dial," } else { "" };
+        writeln!(out, "step,{dial}direction,amount,from,to,zeros")?;
+    }
+    for s in steps {
+        let dir = match s.direction {
+            Direction::Right => "R",
+            Direction::Left => "L",
         };
-        let _ = writeln!(output_writer, "Moving {old_number} to {dir} by {value} ({value_n}) -> {number} |{zero_occurrences}|");
+        match (format, named) {
+            (TraceFormat::Text | TraceFormat::Color, false) => writeln!(
+                out,
+                "Step {}: moving {} to {dir} by {} -> {} |{}|",
+                s.step, s.from, s.clicks, s.to, s.zeros
+            )?,
+            (TraceFormat::Text | TraceFormat::Color, true) => writeln!(
+                out,
+                "Step {}: moving dial {} {} to {dir} by {} -> {} |{}|",
+                s.step,
+                if s.dial.is_empty() { "default" } else { &s.dial },
+                s.from,
+                s.clicks,
+                s.to,
+                s.zeros
+            )?,
+            (TraceFormat::Csv, false) => {
+                writeln!(out, "{},{dir},{},{},{},{}", s.step, s.clicks, s.from, s.to, s.zeros)?
+            }
+            (TraceFormat::Csv, true) => writeln!(
+                out,
+                "{},{},{dir},{},{},{},{}",
+                s.step, s.dial, s.clicks, s.from, s.to, s.zeros
+            )?,
+        }
     }
-    println!("{}", zero_occurrences);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse_lines;
+    use crate::testing::XorShift;
 
+    #[test]
+    fn example() {
+        let test_input = "L68
+L30
+R48
+L5
+R60
+L55
+L1
+L99
+R14
+L82";
+        let rotations = Solver::parse(test_input).unwrap();
+        assert_eq!(Solver::part_one(&rotations), Answer::Number(3));
+        assert_eq!(Solver::part_two(&rotations), Answer::Number(6));
+    }
+
+    #[test]
+    fn small_dial() {
+        let rotations = parse_lines("R3\nL13\nR20\nL1", Rotation::from_line).unwrap();
+        // 0 -> 3 -> 0 -> 0 -> 9
+        let dial = Dial::new(10, 0);
+        assert_eq!(dial.zero_landings(&rotations), 2);
+        // the last rotation leaves zero without passing it again
+        assert_eq!(dial.zero_passes(&rotations), 4);
+    }
+
+    #[test]
+    fn csv_trace() {
+        let mut out = vec![];
+        let safe = Safe::from_input("L68\nL30\nR48").unwrap();
+        write_trace(&safe.trace(), TraceFormat::Csv, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "step,direction,amount,from,to,zeros\n1,L,68,50,82,1\n2,L,30,82,52,1\n3,R,48,52,0,2\n"
+        );
+    }
+
+    #[test]
+    fn several_dials() {
+        let test_input = "dial A 40 0
+dial B 60
+A:R30
+B:L75
+L50
+A:L70
+B:R15";
+        let safe = Solver::parse(test_input).unwrap();
+        assert_eq!(
+            safe.zero_counts(),
+            vec![
+                // 0 -> 30 -> 0
+                ZeroCount { name: "A".to_string(), landings: 1, passes: 2 },
+                // 30 -> 15 -> 30
+                ZeroCount { name: "B".to_string(), landings: 0, passes: 1 },
+                // 50 -> 0
+                ZeroCount { name: "".to_string(), landings: 1, passes: 1 },
+            ]
+        );
+        assert_eq!(Solver::part_one(&safe), Answer::Text("A=1,B=0,default=1".to_string()));
+        let empty = Solver::parse("").unwrap();
+        assert_eq!(Solver::part_one(&empty), Answer::Number(0));
+        assert_eq!(Solver::part_two(&empty), Answer::Number(0));
+
+        let error = Solver::parse("dial A 40\nC:R3").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+        let error = Solver::parse("dial A 40 40").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 11));
+    }
+
+    #[test]
+    fn crossings() {
+        let dial = Dial::default();
+        let right = |clicks| Rotation { dial: 0, direction: Direction::Right, clicks };
+        let left = |clicks| Rotation { dial: 0, direction: Direction::Left, clicks };
+        assert_eq!(dial.zero_crossings(50, &right(49)), 0);
+        assert_eq!(dial.zero_crossings(50, &right(50)), 1);
+        assert_eq!(dial.zero_crossings(50, &right(1000)), 10);
+        assert_eq!(dial.zero_crossings(50, &left(50)), 1);
+        assert_eq!(dial.zero_crossings(0, &left(99)), 0);
+        assert_eq!(dial.zero_crossings(0, &left(100)), 1);
+        assert_eq!(dial.zero_crossings(0, &right(0)), 0);
+        let longest = Rotation::from_line("R9223372036854775807").unwrap();
+        assert_eq!(dial.rotate(50, &longest), 57);
+        assert_eq!(dial.zero_crossings(50, &longest), 92233720368547758);
+        assert_eq!(dial.rotate(50, &left(isize::MAX)), 43);
+    }
+
+    #[test]
+    fn closed_form_matches_clicks() {
+        let mut random = XorShift::new(0x2025_0001);
+        for _ in 0..500 {
+            let size = (random.below(150) + 1) as isize;
+            let start = random.below(size as u64) as isize;
+            let rotations: Vec<Rotation> = (0..random.below(30))
+                .map(|_| Rotation {
+                    dial: 0,
+                    direction: if random.below(2) == 0 { Direction::Left } else { Direction::Right },
+                    clicks: random.below(3 * size as u64 + 2) as isize,
+                })
+                .collect();
+            let dial = Dial::new(size, start);
+            assert_eq!(
+                dial.zero_passes(&rotations),
+                dial.zero_passes_by_click(&rotations),
+                "size {size}, start {start}, rotations {rotations:?}"
+            );
+        }
+    }
 }
@@ -1,5 +1,27 @@
 #![allow(dead_code)]
 use std::collections::HashSet;
+use std::str::FromStr;
+
+use crate::parse::ParseError;
+use crate::solution::{Answer, Solution};
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Model = Tree;
+
+    fn parse(input: &str) -> Result<Self::Model, ParseError> {
+        Tree::from_str(input)
+    }
+
+    fn part_one(tree: &Self::Model) -> Answer {
+        splits_count(tree).into()
+    }
+
+    fn part_two(tree: &Self::Model) -> Answer {
+        timelines_count(tree).into()
+    }
+}
 
 // PART ONE
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
@@ -8,100 +30,102 @@ struct Beam {
     y: usize,
 }
 
-pub fn part_one(input: &str) {
+pub fn splits_count(tree: &Tree) -> u32 {
     let mut split_counter = 0u32;
-    let lines: Vec<&str> = input.lines().collect();
-    let source_line = lines.first().expect("Empty Input");
-    let size = (source_line.len(), lines.len());
+    let size = (tree.width, tree.height);
     let mut beams = vec![
         Beam { 
-            x: source_line.chars().position(|s| s == 'S').unwrap(),
+            x: tree.source_position,
             y: 0
         }
     ];
     // println!("Source Position: {:?}", beams);
     for l in 1..size.1 {
         let mut new_beams = HashSet::new();
+        let splits = tree.get_split_at_line(l);
         for beam in beams.iter().filter(|b| b.y == l - 1) {
-            match lines[l].chars().nth(beam.x).unwrap() {
-                '.' => {
-                    // println!("Continue the Beam at line {l} from {beam:?}");
-                    new_beams.insert(Beam {x: beam.x, y: l});
-                }
-                '^' => {
-                    split_counter +=1;
-                    // println!("Split the Beam at line {l} from {beam:?}");
-                    new_beams.insert(
-                        Beam {
-                            x: (beam.x - 1).clamp(0, size.0),
-                            y: l
-                        }
-                    );
-                    new_beams.insert(
-                        Beam {
-                            x: (beam.x + 1).clamp(0, size.0),
-                            y: l
-                        }
-                    );
-                }
-                _ => { unreachable!() }
+            if splits.contains(&beam.x) {
+                split_counter +=1;
+                // println!("Split the Beam at line {l} from {beam:?}");
+                new_beams.insert(
+                    Beam {
+                        x: (beam.x - 1).clamp(0, size.0),
+                        y: l
+                    }
+                );
+                new_beams.insert(
+                    Beam {
+                        x: (beam.x + 1).clamp(0, size.0),
+                        y: l
+                    }
+                );
+            } else {
+                // println!("Continue the Beam at line {l} from {beam:?}");
+                new_beams.insert(Beam {x: beam.x, y: l});
             }
         }
         beams.extend(new_beams);
         
     }
-    println!("{split_counter}");
+    split_counter
 }
 
 // PART TWO
-struct Tree {
+pub struct Tree {
     source_position: usize,
     splits_position: Vec<Vec<usize>>,
     height: usize,
     width: usize,
 }
 
-impl Tree {
-    fn from_str(input: &str) -> Self {
+impl FromStr for Tree {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
         let lines: Vec<&str> = input.lines().collect();
         let height = lines.len();
-        let source_line = lines.first().expect("Empty Input");
+        let source_line = lines
+            .first()
+            .ok_or_else(|| ParseError::at(input, input, "empty input"))?;
         let width = source_line.len();
-        let source_position = source_line.chars().position(|s| s == 'S').unwrap();
+        let source_position = source_line
+            .chars()
+            .position(|s| s == 'S')
+            .ok_or_else(|| ParseError::at(input, source_line, "the first line has no source (S)"))?;
         let mut splits_position = vec![];
         for line in lines {
+            if line.len() != width {
+                return Err(ParseError::at(input, line, format!("line is {} wide, expected {width}", line.len())));
+            }
             let mut line_splits = vec![];
-            for n in line.chars().enumerate().filter_map(
-                |(n, c)| { 
-                    if c == '^' {
-                        Some(n)
-                    } else {
-                        None
+            for (n, c) in line.char_indices() {
+                match c {
+                    '.' | 'S' => {}
+                    // a splitter on the edge would send the beam out of the manifold
+                    '^' if n == 0 || n == width - 1 => {
+                        return Err(ParseError::at(input, &line[n..n + 1], "splitter on the edge"));
+                    }
+                    '^' => line_splits.push(n),
+                    _ => {
+                        return Err(ParseError::at(input, &line[n..n + c.len_utf8()], format!("'{c}' is not part of the manifold")));
                     }
                 }
-                ) {
-                line_splits.push(n);
             }
             splits_position.push(line_splits);
         }
         // println!("{splits_position:?}");
-        Self { source_position, splits_position, height, width }
+        Ok(Self { source_position, splits_position, height, width })
     }
+}
 
+impl Tree {
     fn get_split_at_line(&self, line: usize) -> &[usize] {
-        let splits = self.splits_position.get(line).unwrap();
-        splits
+        self.splits_position.get(line).unwrap()
     }
 }
 
 
-pub fn part_two(input: &str) {
-    let tree = Tree::from_str(input);
-    let timeline_counter = timelines_count(&tree);
-    println!("Part 2. Timeline counter: {timeline_counter}");
-}
-
-fn timelines_count(tree: &Tree) -> u64 {
+pub fn timelines_count(tree: &Tree) -> u64 {
     let mut counter: Vec<u64> = vec![0; tree.width];
     counter[tree.source_position] = 1;
     for l in 0..tree.height {
@@ -143,7 +167,9 @@ mod tests {
 .^.^.^.^.^...^.
 ...............
 ";
-        part_two(test_input);
+        let tree = Solver::parse(test_input).unwrap();
+        assert_eq!(Solver::part_one(&tree), Answer::Number(21));
+        assert_eq!(Solver::part_two(&tree), Answer::Number(40));
     }
 
 }
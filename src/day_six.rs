@@ -1,5 +1,8 @@
 #![allow(dead_code)]
 
+use crate::parse::ParseError;
+use crate::solution::{Answer, Solution};
+
 #[derive(Debug)]
 enum Operator {
     Add,
@@ -7,49 +10,69 @@ enum Operator {
 }
 
 #[derive(Debug)]
-struct Math<'a> {
-    rows: Vec<Vec<&'a str>>,
+pub struct Math {
+    rows: Vec<Vec<String>>,
     operators: Vec<Operator>,
     columns_width: Vec<u8>
 }
 
-impl<'a> Math<'a> {
-    fn from_input(input: &'a str) -> Self {
-        let mut rows: Vec<Vec<&str>> = vec![];
+impl Math {
+    pub fn from_input(input: &str) -> Result<Self, ParseError> {
+        let mut rows: Vec<Vec<String>> = vec![];
         let mut operators = vec![];
-        let mut input_lines: Vec<&str> = input.lines().collect();
+        let mut input_lines: Vec<&str> = input.lines().filter(|l| !l.trim().is_empty()).collect();
         // last line is operator and column width
-        let last_line = input_lines.pop().unwrap();
+        let last_line = input_lines
+            .pop()
+            .ok_or_else(|| ParseError::at(input, input, "missing the operators line"))?;
         let columns_width = get_column_width(last_line);
         let words = last_line.split_whitespace();
         for word in words {
             match word {
                 "+" => operators.push(Operator::Add),
                 "*" => operators.push(Operator::Mul),
-                _ => panic!("Operator {word} not found, wrong input (or parser? :D)"),
+                _ => return Err(ParseError::at(input, word, format!("'{word}' is not an operator, expected + or *"))),
             }
         }
+        if operators.len() != columns_width.len() {
+            return Err(ParseError::at(
+                input,
+                last_line,
+                format!("{} operators for {} columns, the line must be padded to the last column", operators.len(), columns_width.len()),
+            ));
+        }
 
-        for n in 0..input_lines.len() {
+        for line in input_lines.iter() {
             let mut row_of_cells = vec![];
-            let mut new_line = input_lines[n];
+            let mut new_line = *line;
             for (c, col_width) in columns_width.iter().enumerate() {
-                if c == columns_width.len() - 1 {
-                    row_of_cells.push(&new_line[0..]);
+                let width = *col_width as usize;
+                let last_column = c == columns_width.len() - 1;
+                if new_line.len() < width || (!last_column && new_line.len() < width + 1) {
+                    return Err(ParseError::at(input, &new_line[new_line.len()..], format!("row too short for column {}", c + 1)));
+                }
+                let cell = if last_column { new_line } else { &new_line[0..width] };
+                // a cell is a number aligned with spaces
+                if let Some((n, ch)) = cell.char_indices().find(|(_, ch)| !ch.is_ascii_digit() && *ch != ' ') {
+                    return Err(ParseError::at(input, &cell[n..n + ch.len_utf8()], format!("'{ch}' is not a digit")));
+                }
+                if cell.trim().is_empty() {
+                    return Err(ParseError::at(input, cell, format!("empty cell in column {}", c + 1)));
+                }
+                row_of_cells.push(cell.to_string());
+                if last_column {
                     break
                 }
-                let cell = &new_line[0..(*col_width as usize)];
-                row_of_cells.push(cell);
-                (_, new_line) = new_line.split_at(*col_width as usize + 1);
+                (_, new_line) = new_line.split_at(width + 1);
                 // pkrintln!("Cell: -{cell}-, newline: -{new_line}-");
             }
             rows.push(row_of_cells);
         }
        
-        Self { rows, operators, columns_width }
+        Ok(Self { rows, operators, columns_width })
     }
 
-    fn part_one(&self) -> u64 {
+    pub fn part_one(&self) -> u64 {
         let mut output = 0u64;
         let mut row_output = 0u64;
         for (col, o) in self.operators.iter().enumerate() {
@@ -94,9 +117,9 @@ impl<'a> Math<'a> {
             for c in (0..*width as i32).rev() {
                 let mut digit_char = String::new();
                 for (r, row) in self.rows.iter().enumerate() {
-                    digit_char = format!("{digit_char}{}",row[col].chars().nth(c as usize).expect(&format!("Wrong getting char at column {col} row {r} digit: {c}: Input: {row:?}")));
+                    digit_char = format!("{digit_char}{}",row[col].chars().nth(c as usize).unwrap_or_else(|| panic!("Wrong getting char at column {col} row {r} digit: {c}: Input: {row:?}")));
                 }
-                digits.push(digit_char.trim().parse::<u64>().expect(&format!("Wrong parsing digit -{digit_char}-")));
+                digits.push(digit_char.trim().parse::<u64>().unwrap_or_else(|_| panic!("Wrong parsing digit -{digit_char}-")));
             }
             for (n, digit) in digits.iter().enumerate() {
                 if n == 0 {
@@ -144,20 +167,22 @@ fn get_column_width(operator_line: &str) -> Vec<u8> {
     widths
 }
 
-pub fn part_one() {
+pub struct Solver;
 
-    println!("Hello Day 6 - part 1!");
-    let input = super::load_input("input/input-day6");
-    let math = Math::from_input(&input);
-    println!("Output: {}", math.part_one());
-}
+impl Solution for Solver {
+    type Model = Math;
 
-pub fn part_two() {
+    fn parse(input: &str) -> Result<Self::Model, ParseError> {
+        Math::from_input(input)
+    }
 
-    println!("Hello Day 6 - part 2!");
-    let input = super::load_input("input/input-day6");
-    let math = Math::from_input(&input);
-    println!("Output: {}", math.part_two());
+    fn part_one(math: &Self::Model) -> Answer {
+        math.part_one().into()
+    }
+
+    fn part_two(math: &Self::Model) -> Answer {
+        math.part_two().into()
+    }
 }
 
 #[cfg(test)]
@@ -172,16 +197,9 @@ mod tests {
   6 98  215 314
 *   +   *   +  
 ";
-        // let lines: Vec<&str> = test_input.lines().collect();
-        // let splitted = split_at_4(lines.iter().nth(3).unwrap());
-        // println!("{splitted:?}");
-        let math = Math::from_input(test_input);
-        let output = math.part_two();
-
-        let col_to_print = 3;
-        println!("Printing column {col_to_print}:");
-        math.print_column(col_to_print);
-        println!("Part Two Test Input Output: {output}");
+        let math = Math::from_input(test_input).unwrap();
+        assert_eq!(math.part_one(), 4277556);
+        assert_eq!(math.part_two(), 3263827);
     }
 
 }
@@ -1,41 +1,61 @@
 #![allow(dead_code, unused)]
 
-use super::InputMode;
+use std::str::FromStr;
 
-pub fn part_one() {
-    let mut output: Vec<usize> = vec![];
-    let machines = parse(InputMode::Normal);
-    let output: usize = machines.iter().map(|m| fewest_buttons_to_light(m)).sum();
-    println!("Output: {output}");
+use crate::parse::{ParseError, parse_lines};
+use crate::solution::{Answer, Solution};
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Model = Vec<Machine>;
+
+    fn parse(input: &str) -> Result<Self::Model, ParseError> {
+        parse(input)
+    }
+
+    fn part_one(machines: &Self::Model) -> Answer {
+        total_presses(machines, fewest_buttons_to_light, "lights")
+    }
+
+    // I could not solve part two. The first idea come from
+    // https://www.reddit.com/r/adventofcode/comments/1pk87hl/2025_day_10_part_2_bifurcate_your_way_to_victory/
+    // while this: rust implementation is coded by https://github.com/janek37/advent-of-code/blob/main/2025/day10.rs
+    fn part_two(machines: &Self::Model) -> Answer {
+        total_presses(machines, fewest_buttons_to_joltage, "joltage")
+    }
 }
 
-// I could not solve part two. The first idea come from
-// https://www.reddit.com/r/adventofcode/comments/1pk87hl/2025_day_10_part_2_bifurcate_your_way_to_victory/
-// while this: rust implementation is coded by https://github.com/janek37/advent-of-code/blob/main/2025/day10.rs
-pub fn part_two() {
-    let mut output: Vec<usize> = vec![];
-    let machines = parse(InputMode::Normal);
-    let output: usize = machines.iter().map(|m| fewest_buttons_to_joltage(m)).sum();
-    println!("Output: {output}");
+fn total_presses(machines: &[Machine], fewest: fn(&Machine) -> Option<usize>, goal: &str) -> Answer {
+    let mut total = 0;
+    for (n, machine) in machines.iter().enumerate() {
+        match fewest(machine) {
+            Some(presses) => total += presses,
+            None => return Answer::Error(format!("machine {}: no buttons reach the {goal}", n + 1)),
+        }
+    }
+    total.into()
 }
 
-fn fewest_buttons_to_light(machine: &Machine) -> usize {
+/// Fewest presses turning on the lights, `None` when no buttons do.
+pub fn fewest_buttons_to_light(machine: &Machine) -> Option<usize> {
     let binary_buttons = get_binary_buttons(&machine.buttons);
     for subgroup in subgroups(&binary_buttons) {
         if subgroup.iter().fold(0, |a, &b| a ^ b) == machine.lights {
-            return subgroup.len()
+            return Some(subgroup.len())
         }
     }
-    unreachable!()
+    None
 }
 
-fn fewest_buttons_to_joltage(machine: &Machine) -> usize {
+/// Fewest presses reaching the joltage, `None` when no buttons do.
+pub fn fewest_buttons_to_joltage(machine: &Machine) -> Option<usize> {
     let binary_buttons = get_binary_buttons(&machine.buttons);
     let subset_xors: Vec<_> = subgroups(&binary_buttons)
         .iter()
         .map(|subset| (subset.to_owned(), subset.iter().fold(0, |a, &b| a ^ b)))
         .collect();
-    fewest_joltage_presses_recur(&subset_xors, &machine.joltage).unwrap()
+    fewest_joltage_presses_recur(&subset_xors, &machine.joltage)
 }
 
 fn fewest_joltage_presses_recur(subset_xors: &[(Vec<u32>, u32)], joltages: &[i32]) -> Option<usize> {
@@ -46,7 +66,7 @@ fn fewest_joltage_presses_recur(subset_xors: &[(Vec<u32>, u32)], joltages: &[i32
     let mut best = None;
     for (subset, xor) in subset_xors {
         if *xor == binary_joltages {
-            let new_joltages = get_new_joltages(joltages, &subset);
+            let new_joltages = get_new_joltages(joltages, subset);
             if new_joltages.iter().all(|&j| j >= 0) {
                 let press_count = fewest_joltage_presses_recur(
                     subset_xors, &new_joltages
@@ -112,61 +132,101 @@ fn get_all_combinations(group: &[u32], count: usize) -> Vec<Vec<u32>> {
     }
 }
 
-struct Machine {
+pub struct Machine {
     lights: u32,
     joltage: Vec<i32>,
     buttons: Vec<Vec<u32>>,
 }
 
-impl Machine {
-    fn from_str(input: &str) -> Self {
-        let (light_str, rest) = input.split_once(']').unwrap();
-        let (buttons_str, joltage_str) = rest.split_once('{').unwrap();
-
-        let lights = light_str[1..]
-            .trim()
-            .chars()
-            .enumerate()
-            .filter(|&(_, c)| c == '#')
-            .map(|(n, _)| 1 << n)
-            .sum();
+impl FromStr for Machine {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let error = |field: &str, message: String| ParseError::at(input, field, message);
+        let line = input.trim_end();
+        let light_str = line
+            .strip_prefix('[')
+            .ok_or_else(|| error(line, "a machine starts with its lights diagram [...]".to_string()))?;
+        let (light_str, rest) = light_str
+            .split_once(']')
+            .ok_or_else(|| error(light_str, "unclosed lights diagram".to_string()))?;
+        let (buttons_str, joltage_str) = rest
+            .split_once('{')
+            .ok_or_else(|| error(rest, "missing the joltage requirements {...}".to_string()))?;
+
+        let light_str = light_str.trim();
+        if light_str.len() > u32::BITS as usize {
+            return Err(error(light_str, format!("{} lights, at most {} are supported", light_str.len(), u32::BITS)));
+        }
+        let mut lights = 0;
+        for (n, c) in light_str.char_indices() {
+            match c {
+                '#' => lights += 1 << n,
+                '.' => {}
+                _ => return Err(error(&light_str[n..n + c.len_utf8()], format!("'{c}' is not a light, expected . or #"))),
+            }
+        }
+        let lights_count = light_str.len() as u32;
         let mut buttons: Vec<Vec<u32>> = vec![];
 
-        for button_str in buttons_str.trim().split(" ") {
+        for button_str in buttons_str.split_whitespace() {
             let mut button: Vec<u32> = vec![];
-            let button_triggers = &button_str[1..button_str.len() - 1];
+            let button_triggers = button_str
+                .strip_prefix('(')
+                .and_then(|b| b.strip_suffix(')'))
+                .ok_or_else(|| error(button_str, format!("'{button_str}' is not a button (...)")))?;
             for trigger in button_triggers.split(",") {
-                let trigger = trigger.parse::<u32>().unwrap();
+                let trigger = trigger
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|t| *t < lights_count)
+                    .ok_or_else(|| error(trigger, format!("'{trigger}' is not one of the {lights_count} lights")))?;
                 button.push(trigger);
             }
             buttons.push(button);
         }
         let mut joltage: Vec<i32> = vec![];
-        let joltage_str = &joltage_str[..joltage_str.len() - 1];
+        let joltage_str = joltage_str
+            .strip_suffix('}')
+            .ok_or_else(|| error(joltage_str, "unclosed joltage requirements".to_string()))?;
         for j in joltage_str.split(",") {
-            joltage.push(j.parse().unwrap());
+            joltage.push(j.parse().map_err(|e| error(j, format!("'{j}' is not a joltage: {e}")))?);
         }
-        Self {
+        if joltage.len() != light_str.len() {
+            return Err(error(joltage_str, format!("{} joltages for {} lights", joltage.len(), light_str.len())));
+        }
+        Ok(Self {
             lights,
             joltage,
             buttons,
-        }
+        })
     }
 }
 
-fn parse(mode: InputMode) -> Vec<Machine> {
-    let input: String;
-    match mode {
-        InputMode::Example => {
-            input = "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
+pub fn parse(input: &str) -> Result<Vec<Machine>, ParseError> {
+    parse_lines(input, Machine::from_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn machines() {
+        let test_input = "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
 [...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}
-[.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}
-"
-            .to_string();
-        }
-        InputMode::Normal => {
-            input = super::load_input("input/input-day10");
-        }
+[.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}";
+        let machines = Solver::parse(test_input).unwrap();
+        let presses: Vec<_> = machines.iter().map(fewest_buttons_to_light).collect();
+        assert_eq!(presses, vec![Some(2), Some(3), Some(2)]);
+        assert_eq!(Solver::part_one(&machines), Answer::Number(7));
+        assert_eq!(Solver::part_two(&machines), Answer::Number(33));
+    }
+
+    #[test]
+    fn unsolvable() {
+        let machines = Solver::parse("[.#] (1) {0,1}\n[#.] (1) {1,1}").unwrap();
+        assert_eq!(Solver::part_one(&machines), Answer::Error("machine 2: no buttons reach the lights".to_string()));
+        assert_eq!(Solver::part_two(&machines), Answer::Error("machine 2: no buttons reach the joltage".to_string()));
     }
-    input.lines().map(|l| Machine::from_str(l)).collect()
 }
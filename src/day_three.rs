@@ -1,143 +1,634 @@
 #![allow(dead_code)]
-use std::cmp::Ordering;
+use std::fmt::Display;
+use std::io::{self, BufRead, Write};
 
-pub fn part_one() {
-    println!("Hello Day 3 - part 1!");
-    let input = super::load_input("input/input-day3");
-    let mut output: u32 = 0;
-    let banks_input: Vec<&str> = input.trim().split("\n").collect();
-    for line in banks_input.iter() {
-        let bank = Bank::from_line(line);
-        output += bank.max_joltage_part_one();
+use crate::parse::{ParseError, parse_lines};
+use crate::solution::{Answer, Solution, TraceFormat};
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Model = Vec<Bank>;
+
+    fn parse(input: &str) -> Result<Self::Model, ParseError> {
+        parse_lines(input, Bank::from_line)
+    }
+
+    fn part_one(banks: &Self::Model) -> Answer {
+        total_joltage(banks, PART_ONE_BATTERIES)
+    }
+
+    fn part_two(banks: &Self::Model) -> Answer {
+        total_joltage(banks, PART_TWO_BATTERIES)
+    }
+
+    // the batteries turned on in each bank
+    fn trace(banks: &Self::Model, format: TraceFormat, out: &mut dyn Write) -> io::Result<bool> {
+        if format == TraceFormat::Csv {
+            writeln!(out, "part,bank,joltage,indices")?;
+        }
+        for (part, count) in [(1, PART_ONE_BATTERIES), (2, PART_TWO_BATTERIES)] {
+            for (n, bank) in banks.iter().enumerate() {
+                let (selection, joltage) = match bank.select(count) {
+                    Ok(selection) => {
+                        let joltage = selection.joltage.map_or_else(|| bank.digits(&selection), |j| j.to_string());
+                        (selection, joltage)
+                    }
+                    // the bank is written without any battery on
+                    Err(e) => {
+                        match format {
+                            TraceFormat::Text | TraceFormat::Color => {
+                                let bank = bank.highlight(&Selection::default(), Highlight::Brackets);
+                                writeln!(out, "part {part} - {bank} -> {e}")?
+                            }
+                            TraceFormat::Csv => writeln!(out, "{part},{},,", n + 1)?,
+                        }
+                        continue;
+                    }
+                };
+                match format {
+                    TraceFormat::Text => writeln!(
+                        out,
+                        "part {part} - {} -> {}",
+                        bank.highlight(&selection, Highlight::Brackets),
+                        joltage
+                    )?,
+                    TraceFormat::Color => writeln!(
+                        out,
+                        "part {part} - {} -> {}",
+                        bank.highlight(&selection, Highlight::Ansi),
+                        joltage
+                    )?,
+                    TraceFormat::Csv => {
+                        let indices: Vec<String> = selection.indices.iter().map(usize::to_string).collect();
+                        writeln!(out, "{part},{},{joltage},{}", n + 1, indices.join(" "))?
+                    }
+                }
+            }
+        }
+        Ok(true)
     }
-    println!("Final Output: {output}");
 }
 
-pub fn part_two() {
-    // println!("Hello Day 3 - part 2!");
-    let input = super::load_input("input/input-day3");
-    let mut output: u64 = 0;
-    let banks_input: Vec<&str> = input.trim().split("\n").collect();
-    for line in banks_input.iter() {
-        let bank = Bank::from_line(line);
-        output += bank.max_joltage_part_two();
+const PART_ONE_BATTERIES: usize = 2;
+const PART_TWO_BATTERIES: usize = 12;
+
+// a bank too small for the part has no answer
+fn total_joltage(banks: &[Bank], count: usize) -> Answer {
+    let mut total: u64 = 0;
+    for (n, bank) in banks.iter().enumerate() {
+        match bank.max_joltage(count) {
+            Ok(joltage) => total += joltage,
+            Err(e) => return Answer::Error(format!("bank {}: {e}", n + 1)),
+        }
     }
-    println!("Final Output: {output}");
+    total.into()
 }
 
-struct Bank {
-    batteries: Vec<u32>
+/// A bank of batteries, a damaged one (`x`) can't be turned on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bank {
+    batteries: Vec<Option<u32>>
 }
 
 impl Bank {
-    fn from_line(line: &str) -> Self {
-        let batteries: Vec<u32> = line.chars().flat_map(|ch| ch.to_digit(10)).collect();
-        Self { batteries }
-    }
-
-    fn max_joltage_part_one(&self) -> u32 {
-        let max_1: (usize, &u32) = self.batteries
-            .iter()
-            .enumerate()
-            .filter(|(idx, _)| idx < &(self.batteries.len() - 1))
-            .max_by(|(id0, value0), (id1, value1)|
-                match value0.cmp(value1) {
-                    Ordering::Equal => {
-                        // prefer the lower ids when max value
-                        // is equal
-                        if id0 > id1  {
-                            Ordering::Less
-                        } else {
-                            Ordering::Greater
-                        }
-                    },
-                    Ordering::Less => {
-                        Ordering::Less
-                    },
-                    Ordering::Greater => Ordering::Greater,
+    pub fn from_line(line: &str) -> Result<Self, ParseError> {
+        let line = line.trim_end();
+        let mut batteries: Vec<Option<u32>> = Vec::with_capacity(line.len());
+        for (n, ch) in line.char_indices() {
+            match ch.to_digit(10) {
+                Some(joltage) => batteries.push(Some(joltage)),
+                None if ch == DAMAGED => batteries.push(None),
+                None => {
+                    let field = &line[n..n + ch.len_utf8()];
+                    return Err(ParseError::at(line, field, format!("'{ch}' is not a battery joltage")));
                 }
-            )
-            .unwrap();
-        let max_2: (usize, &u32) = self.batteries
-            .iter()
-            .enumerate()
-            .filter(|(idx, _)| idx > &max_1.0)
-            .max_by(|(_, value0), (_, value1)| value0.cmp(value1))
-            .unwrap();
-        let output = max_1.1 * 10 + max_2.1;
-        // println!("Max1: {max_1:?} - Max2: {max_2:?}: {output}");
-        output
+            }
+        }
+        Ok(Self { batteries })
     }
 
-    fn max_joltage_part_two(&self) -> u64 {
-        let capacity = 12;
-        let mut max: Vec<(usize, &u32)> = Vec::with_capacity(capacity);
-        for _ in 0 ..capacity {
-            let list = self.batteries
-                .iter()
-                .enumerate()
-                .filter(|(idx, _)| {
-                    if idx < &(self.batteries.len() - capacity + max.len() + 1) {
-                        if let Some((last_max_idx, _)) = max.last() {
-                            if idx > last_max_idx {
-                                return true;
-                            }
-                        } else {
-                            return true;
-                        }
-                    }
-                    return false;
-                })
-                .max_by(|(id0, value0), (id1, value1)|
-                    match value0.cmp(value1) {
-                        Ordering::Equal => {
-                            // prefer the lower ids when max value
-                            // is equal
-                            if id0 > id1  {
-                                Ordering::Less
-                            } else {
-                                Ordering::Greater
-                            }
-                        },
-                        Ordering::Less => {
-                            Ordering::Less
-                        },
-                        Ordering::Greater => Ordering::Greater,
-                    }
-                )
-                .unwrap();
-            max.push((list.0, list.1));
+    /// The joltage of every battery, none for the damaged ones.
+    pub fn batteries(&self) -> &[Option<u32>] {
+        &self.batteries
+    }
+
+    /// Largest joltage made by turning on `count` batteries, their digits
+    /// read in the bank order.
+    pub fn max_joltage(&self, count: usize) -> Result<u64, JoltageError> {
+        self.select(count)?.joltage.ok_or(JoltageError::TooManyDigits { wanted: count })
+    }
+
+    /// The batteries giving the largest joltage with `count` batteries on.
+    pub fn select(&self, count: usize) -> Result<Selection, JoltageError> {
+        self.solve(&Constraints::new(count))
+    }
+
+    /// The batteries giving the best joltage under the constraints.
+    /// Any count is selected without a spacing, while the spaced
+    /// selection compares joltages and stops at [`MAX_DIGITS`].
+    pub fn solve(&self, constraints: &Constraints) -> Result<Selection, JoltageError> {
+        let count = constraints.count;
+        if constraints.spacing > 1 && count > MAX_DIGITS {
+            return Err(JoltageError::TooManyDigits { wanted: count });
         }
-        let mut output = 0;
-        for (n, (_, m)) in max.into_iter().enumerate() {
-            output += *m as u64 * (10usize.pow((capacity - n - 1) as u32)) as u64;
+        let working: Vec<usize> = (0..self.batteries.len()).filter(|n| self.batteries[*n].is_some()).collect();
+        if count > working.len() {
+            return Err(JoltageError::TooFewBatteries { wanted: count, available: working.len() });
+        }
+        let indices = if constraints.spacing <= 1 {
+            self.select_by_stack(&working, count, constraints.goal)
+        } else {
+            self.select_by_table(constraints).ok_or(JoltageError::NoSelection { wanted: count })?
+        };
+        let joltage =
+            (count <= MAX_DIGITS).then(|| indices.iter().fold(0, |output, n| output * 10 + self.joltage(*n)));
+        Ok(Selection { joltage, indices })
+    }
+
+    /// The digits of the selected batteries, however many they are.
+    pub fn digits(&self, selection: &Selection) -> String {
+        selection.indices.iter().map(|n| self.joltage(*n).to_string()).collect()
+    }
+
+    fn joltage(&self, n: usize) -> u64 {
+        self.batteries[n].expect("a selected battery works") as u64
+    }
+
+    // The kept batteries are a stack: a battery pushes out the worse ones
+    // before it, while enough batteries are left to fill the stack.
+    // Damaged batteries are simply left out.
+    fn select_by_stack(&self, working: &[usize], count: usize, goal: Goal) -> Vec<usize> {
+        let mut drops = working.len() - count;
+        let mut stack: Vec<usize> = Vec::with_capacity(working.len());
+        for &n in working.iter() {
+            while drops > 0 && stack.last().is_some_and(|top| goal.better(self.joltage(n), self.joltage(*top))) {
+                stack.pop();
+                drops -= 1;
+            }
+            stack.push(n);
+        }
+        stack.truncate(count);
+        stack
+    }
+
+    // With a spacing the stack no longer works: best[n][c] is the best
+    // joltage of c batteries taken from the n-th one on, the selection is
+    // then rebuilt from the first battery.
+    fn select_by_table(&self, constraints: &Constraints) -> Option<Vec<usize>> {
+        let (len, count, goal) = (self.batteries.len(), constraints.count, constraints.goal);
+        let mut best = vec![vec![None; count + 1]; len + 1];
+        best.iter_mut().for_each(|row| row[0] = Some(0));
+        // joltage of c batteries starting with the n-th one
+        let taking = |best: &[Vec<Option<u64>>], n: usize, c: usize| -> Option<u64> {
+            let rest = best[(n + constraints.spacing).min(len)][c - 1]?;
+            Some(self.batteries[n]? as u64 * 10u64.pow(c as u32 - 1) + rest)
+        };
+        for n in (0..len).rev() {
+            for c in 1..=count {
+                best[n][c] = match (best[n + 1][c], taking(&best, n, c)) {
+                    (Some(skip), Some(take)) => Some(if goal.better(take, skip) { take } else { skip }),
+                    (skip, take) => skip.or(take),
+                };
+            }
+        }
+        best[0][count]?;
+        let mut indices = Vec::with_capacity(count);
+        let (mut n, mut c) = (0, count);
+        while c > 0 {
+            // the earliest battery reaching the best joltage
+            if taking(&best, n, c).is_some() && taking(&best, n, c) == best[n][c] {
+                indices.push(n);
+                n += constraints.spacing;
+                c -= 1;
+            } else {
+                n += 1;
+            }
+        }
+        Some(indices)
+    }
+
+    /// The bank digits with the selected batteries highlighted.
+    pub fn highlight(&self, selection: &Selection, style: Highlight) -> String {
+        let mut selected = selection.indices.iter().peekable();
+        let mut output = String::new();
+        for (n, battery) in self.batteries.iter().enumerate() {
+            let joltage = battery.map_or(DAMAGED.to_string(), |j| j.to_string());
+            if selected.next_if_eq(&&n).is_none() {
+                output.push_str(&joltage);
+                continue;
+            }
+            match style {
+                Highlight::Brackets => output.push_str(&format!("[{joltage}]")),
+                Highlight::Ansi => output.push_str(&format!("\x1b[1;32m{joltage}\x1b[0m")),
+            }
         }
-        // println!("Output: {output}");
         output
     }
 }
 
+// a damaged battery in a bank
+const DAMAGED: char = 'x';
+
+/// Whether the largest or the smallest joltage is wanted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Goal {
+    #[default]
+    Max,
+    Min,
+}
+
+impl Goal {
+    fn better(&self, a: u64, b: u64) -> bool {
+        match self {
+            Goal::Max => a > b,
+            Goal::Min => a < b,
+        }
+    }
+}
+
+/// How the batteries of a bank are chosen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Constraints {
+    /// batteries to turn on
+    pub count: usize,
+    pub goal: Goal,
+    /// smallest distance between two batteries turned on,
+    /// 1 lets neighbours be both on
+    pub spacing: usize,
+}
+
+impl Constraints {
+    pub fn new(count: usize) -> Self {
+        Self { count, goal: Goal::Max, spacing: 1 }
+    }
+
+    pub fn goal(self, goal: Goal) -> Self {
+        Self { goal, ..self }
+    }
+
+    pub fn spacing(self, spacing: usize) -> Self {
+        Self { spacing, ..self }
+    }
+}
+
+/// The batteries turned on in a bank, by increasing index.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Selection {
+    /// none past [`MAX_DIGITS`] batteries, see [`Bank::digits`]
+    pub joltage: Option<u64>,
+    pub indices: Vec<usize>,
+}
+
+/// How the selected batteries stand out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Highlight {
+    /// bold green
+    Ansi,
+    Brackets,
+}
+
+/// A joltage of more digits doesn't fit in a u64.
+pub const MAX_DIGITS: usize = 19;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JoltageError {
+    TooFewBatteries { wanted: usize, available: usize },
+    TooManyDigits { wanted: usize },
+    /// the batteries are too close for the spacing
+    NoSelection { wanted: usize },
+}
+
+impl Display for JoltageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JoltageError::TooFewBatteries { wanted, available } => {
+                write!(f, "can't turn on {wanted} batteries in a bank of {available}")
+            }
+            JoltageError::TooManyDigits { wanted } => {
+                write!(f, "a joltage of {wanted} batteries has more than {MAX_DIGITS} digits")
+            }
+            JoltageError::NoSelection { wanted } => {
+                write!(f, "no {wanted} batteries are far enough from each other")
+            }
+        }
+    }
+}
+
+impl std::error::Error for JoltageError {}
+
+/// Largest joltages of a bank read one battery at a time.
+///
+/// Instead of the bank, only the best joltage of every battery count
+/// up to the largest one is kept: a new battery either extends the best
+/// joltage of one battery less, or is skipped.
+#[derive(Debug, Clone)]
+pub struct JoltageStream {
+    // best[n]: largest joltage with n batteries on, none if fewer were read
+    best: Vec<Option<u64>>,
+    batteries: usize,
+}
+
+impl JoltageStream {
+    pub fn new(max_count: usize) -> Result<Self, JoltageError> {
+        if max_count > MAX_DIGITS {
+            return Err(JoltageError::TooManyDigits { wanted: max_count });
+        }
+        let mut best = vec![None; max_count + 1];
+        best[0] = Some(0);
+        Ok(Self { best, batteries: 0 })
+    }
+
+    pub fn push(&mut self, joltage: u32) {
+        for n in (1..self.best.len()).rev() {
+            if let Some(shorter) = self.best[n - 1] {
+                let extended = shorter * 10 + joltage as u64;
+                self.best[n] = self.best[n].max(Some(extended));
+            }
+        }
+        self.batteries += 1;
+    }
+
+    pub fn max_joltage(&self, count: usize) -> Result<u64, JoltageError> {
+        match self.best.get(count) {
+            None => Err(JoltageError::TooManyDigits { wanted: count }),
+            Some(None) => Err(JoltageError::TooFewBatteries { wanted: count, available: self.batteries }),
+            Some(Some(joltage)) => Ok(*joltage),
+        }
+    }
+
+    // ready for the next bank
+    pub fn reset(&mut self) {
+        self.best.fill(None);
+        self.best[0] = Some(0);
+        self.batteries = 0;
+    }
+}
+
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl Display for StreamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StreamError::Io(e) => write!(f, "can't read the banks: {e}"),
+            StreamError::Parse(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for StreamError {}
+
+impl From<io::Error> for StreamError {
+    fn from(e: io::Error) -> Self {
+        StreamError::Io(e)
+    }
+}
+
+/// Total joltage of the banks for each battery count of `counts`,
+/// reading the banks in a single pass: the memory used depends on the
+/// largest count, not on the size of the banks. The totals can't overflow,
+/// whatever the number of banks.
+///
+/// This is a library function only: `run` still reads the whole input
+/// in memory, a bank dump too large for it is read by calling this
+/// function with its own reader.
+pub fn stream_total_joltage(mut reader: impl BufRead, counts: &[usize]) -> Result<Vec<u128>, StreamError> {
+    let max_count = counts.iter().copied().max().unwrap_or(0);
+    let error = |line, column, message: String| StreamError::Parse(ParseError { line, column, message });
+    let mut stream = JoltageStream::new(max_count).map_err(|e| error(1, 1, e.to_string()))?;
+    let mut totals = vec![0; counts.len()];
+    let (mut line, mut column) = (1, 1);
+    // a line of damaged batteries is a bank, as for Bank::from_line
+    let mut blank = true;
+
+    let mut end_bank = |stream: &mut JoltageStream, line| -> Result<(), StreamError> {
+        for (total, count) in totals.iter_mut().zip(counts) {
+            *total += stream.max_joltage(*count).map_err(|e| error(line, 1, e.to_string()))? as u128;
+        }
+        stream.reset();
+        Ok(())
+    };
+    loop {
+        let chunk = reader.fill_buf()?;
+        if chunk.is_empty() {
+            break;
+        }
+        for byte in chunk {
+            match byte {
+                b'\n' => {
+                    // blank lines are skipped
+                    if !blank {
+                        end_bank(&mut stream, line)?;
+                    }
+                    (line, column, blank) = (line + 1, 0, true);
+                }
+                b'\r' => {}
+                digit @ b'0'..=b'9' => {
+                    stream.push((digit - b'0') as u32);
+                    blank = false;
+                }
+                // damaged batteries are never turned on
+                b'x' => blank = false,
+                other => {
+                    return Err(error(line, column, format!("'{}' is not a battery joltage", other.escape_ascii())));
+                }
+            }
+            column += 1;
+        }
+        let read = chunk.len();
+        reader.consume(read);
+    }
+    if !blank {
+        end_bank(&mut stream, line)?;
+    }
+    Ok(totals)
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::XorShift;
 
     #[test]
     fn battery() {
-        let test_input = "";
+        let test_input = "987654321111111
+811111111111119
+234234234234278
+818181911112111";
         let banks_input: Vec<&str> = test_input.trim().split("\n").collect();
-        let mut output = 0;
+        let mut output = vec![];
         for line in banks_input.iter() {
-            let bank = Bank::from_line(line);
-            println!("Bank: {:?}", bank.batteries);
-            output += bank.max_joltage_part_two();
+            let bank = Bank::from_line(line).unwrap();
+            output.push((bank.max_joltage(2).unwrap(), bank.max_joltage(12).unwrap()));
+        }
+        assert_eq!(
+            output,
+            vec![
+                (98, 987654321111),
+                (89, 811111111119),
+                (78, 434234234278),
+                (92, 888911112111),
+            ]
+        );
+        let banks = Solver::parse(test_input).unwrap();
+        assert_eq!(Solver::part_one(&banks), Answer::Number(357));
+        assert_eq!(Solver::part_two(&banks), Answer::Number(3121910778619));
+    }
+
+    #[test]
+    fn selection() {
+        let bank = Bank::from_line("818181911112111").unwrap();
+        let selection = bank.select(2).unwrap();
+        assert_eq!(selection, Selection { joltage: Some(92), indices: vec![6, 11] });
+        assert_eq!(bank.highlight(&selection, Highlight::Brackets), "818181[9]1111[2]111");
+        assert_eq!(
+            bank.highlight(&bank.select(3).unwrap(), Highlight::Ansi),
+            "818181\x1b[1;32m9\x1b[0m1111\x1b[1;32m2\x1b[0m\x1b[1;32m1\x1b[0m11"
+        );
+    }
+
+    #[test]
+    fn streaming() {
+        let test_input = "987654321111111
+811111111111119
+
+234234234234278
+818181911112111
+";
+        let totals = stream_total_joltage(test_input.as_bytes(), &[2, 12]).unwrap();
+        assert_eq!(totals, vec![357, 3121910778619]);
+
+        // same joltages as the stack, for every count
+        let bank = Bank::from_line("2342342342342783141592653").unwrap();
+        let mut stream = JoltageStream::new(19).unwrap();
+        bank.batteries().iter().flatten().for_each(|j| stream.push(*j));
+        for count in 0..=19 {
+            assert_eq!(stream.max_joltage(count), bank.max_joltage(count), "{count} batteries");
+        }
+
+        let Err(StreamError::Parse(error)) = stream_total_joltage("12345\n12?45\n".as_bytes(), &[2]) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((error.line, error.column), (2, 3));
+        let Err(StreamError::Parse(error)) = stream_total_joltage("12345\n1\n".as_bytes(), &[2]) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(error.to_string(), "line 2, column 1: can't turn on 2 batteries in a bank of 1");
+        // a bank of damaged batteries is not a blank line
+        let Err(StreamError::Parse(error)) = stream_total_joltage("12345\nxxx\n\n".as_bytes(), &[2]) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(error.to_string(), "line 2, column 1: can't turn on 2 batteries in a bank of 0");
+        assert_eq!(
+            Bank::from_line("xxx").unwrap().max_joltage(2),
+            Err(JoltageError::TooFewBatteries { wanted: 2, available: 0 })
+        );
+
+        // chunks smaller than a line, and totals past u64
+        let bank = "9".repeat(19);
+        let banks = format!("{bank}\n{bank}\r\n\n");
+        let reader = io::BufReader::with_capacity(4, banks.as_bytes());
+        assert_eq!(stream_total_joltage(reader, &[2, 19]).unwrap(), vec![99 + 99, 19999999999999999998]);
+        assert_eq!(stream_total_joltage("1x1\n9x\n".as_bytes(), &[1]).unwrap(), vec![1 + 9]);
+    }
+
+    #[test]
+    fn constraints() {
+        let bank = Bank::from_line("81x181911x12111").unwrap();
+        assert_eq!(bank.max_joltage(2), Ok(92));
+        let min = bank.solve(&Constraints::new(3).goal(Goal::Min)).unwrap();
+        assert_eq!(bank.highlight(&min, Highlight::Brackets), "8[1]x[1]8[1]911x12111");
+        assert_eq!(min.joltage, Some(111));
+        let spaced = bank.solve(&Constraints::new(3).spacing(3)).unwrap();
+        assert_eq!(spaced, Selection { joltage: Some(921), indices: vec![6, 11, 14] });
+        assert_eq!(
+            bank.solve(&Constraints::new(6).spacing(3)),
+            Err(JoltageError::NoSelection { wanted: 6 })
+        );
+    }
+
+    // every choice of `count` batteries, kept when they are spaced enough
+    fn brute_force(bank: &Bank, constraints: &Constraints) -> Option<u64> {
+        let len = bank.batteries().len();
+        let mut best: Option<u64> = None;
+        for mask in 0u32..1 << len {
+            let indices: Vec<usize> = (0..len).filter(|n| mask & (1 << n) != 0).collect();
+            if indices.len() != constraints.count
+                || indices.windows(2).any(|w| w[1] - w[0] < constraints.spacing)
+                || indices.iter().any(|n| bank.batteries()[*n].is_none())
+            {
+                continue;
+            }
+            let joltage = indices.iter().fold(0, |output, n| output * 10 + bank.batteries()[*n].unwrap() as u64);
+            if best.is_none_or(|b| constraints.goal.better(joltage, b)) {
+                best = Some(joltage);
+            }
+        }
+        best
+    }
+
+    #[test]
+    fn constraints_match_brute_force() {
+        let mut random = XorShift::new(0x2025_0003);
+        for _ in 0..300 {
+            let line: String = (0..random.below(12) + 1)
+                .map(|_| match random.below(12) {
+                    10 | 11 => 'x',
+                    digit => char::from_digit(digit as u32, 10).unwrap(),
+                })
+                .collect();
+            let bank = Bank::from_line(&line).unwrap();
+            let goal = if random.below(2) == 0 { Goal::Max } else { Goal::Min };
+            let constraints =
+                Constraints::new(random.below(5) as usize).goal(goal).spacing(random.below(4) as usize);
+            let solved = bank.solve(&constraints);
+            assert_eq!(solved.as_ref().ok().and_then(|s| s.joltage), brute_force(&bank, &constraints), "{line} {constraints:?}");
+            if let Ok(selection) = solved {
+                let joltage = selection.indices.iter().fold(0, |o, n| o * 10 + bank.batteries()[*n].unwrap() as u64);
+                assert_eq!(Some(joltage), selection.joltage, "{line} {constraints:?}");
+            }
         }
-        println!("Final Output: {output}");
     }
 
     #[test]
-    fn day3_part2() {
-        super::part_two();
+    fn joltage_errors() {
+        let bank = Bank::from_line("12345").unwrap();
+        assert_eq!(bank.max_joltage(5), Ok(12345));
+        assert_eq!(bank.max_joltage(0), Ok(0));
+        assert_eq!(
+            bank.max_joltage(6),
+            Err(JoltageError::TooFewBatteries { wanted: 6, available: 5 })
+        );
+        assert_eq!(
+            bank.max_joltage(6).unwrap_err().to_string(),
+            "can't turn on 6 batteries in a bank of 5"
+        );
+        // more batteries than a u64 holds are still selected
+        let bank = Bank::from_line("2342342342342783141592653x58979").unwrap();
+        let selection = bank.select(22).unwrap();
+        assert_eq!(selection.joltage, None);
+        assert_eq!(bank.digits(&selection), "4444278314159265358979");
+        assert_eq!(bank.select(30).unwrap().indices.len(), 30);
+        assert_eq!(bank.max_joltage(22), Err(JoltageError::TooManyDigits { wanted: 22 }));
+        assert_eq!(
+            bank.solve(&Constraints::new(20).goal(Goal::Min)).unwrap().joltage,
+            None
+        );
+        assert_eq!(
+            bank.solve(&Constraints::new(20).spacing(2)),
+            Err(JoltageError::TooManyDigits { wanted: 20 })
+        );
+        // part one is solved even when the banks are too small for part two
+        let banks = Solver::parse("123\n456").unwrap();
+        assert_eq!(Solver::part_one(&banks), Answer::Number(23 + 56));
+        assert_eq!(
+            Solver::part_two(&banks),
+            Answer::Error("bank 1: can't turn on 12 batteries in a bank of 3".to_string())
+        );
     }
 
 }
@@ -1,13 +1,16 @@
 #![allow(dead_code)]
-use super::InputMode;
 use std::{
     fmt::Display,
     ops::{Index, IndexMut},
     cmp::Ordering,
+    str::FromStr,
 };
 
+use crate::parse::{ParseError, parse_lines};
+use crate::solution::{Answer, Solution};
+
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
-enum Unit {
+pub enum Unit {
     Solid(char),
     Empty,
 }
@@ -15,23 +18,40 @@ enum Unit {
 // NeighBour 4 directions
 const NB4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
 
-struct Problem {
+#[derive(Clone)]
+pub struct Problem {
     grid_size: (usize, usize),
     required_shapes: [u16; 6],
 }
-impl Problem {
-    fn from_str(input: &str) -> Self {
+impl FromStr for Problem {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
         let mut required_shapes = [0; 6];
-        let (size_str, reqs) = input.split_once(":").unwrap();
-        let (grid_width, grid_height) = size_str.split_once("x").unwrap();
-        for (n, r) in reqs.trim().split_whitespace().enumerate() {
-            required_shapes[n] = r.parse::<u16>().unwrap();
+        let (size_str, reqs) = input
+            .split_once(":")
+            .ok_or_else(|| ParseError::at(input, input, "expected <width>x<height>: <shapes>"))?;
+        let (grid_width, grid_height) = size_str
+            .split_once("x")
+            .ok_or_else(|| ParseError::at(input, size_str, format!("'{size_str}' is not a size <width>x<height>")))?;
+        for (n, r) in reqs.split_whitespace().enumerate() {
+            if n >= required_shapes.len() {
+                return Err(ParseError::at(input, r, format!("only {} shapes can be required", required_shapes.len())));
+            }
+            required_shapes[n] = r
+                .parse::<u16>()
+                .map_err(|e| ParseError::at(input, r, format!("'{r}' is not a number of shapes: {e}")))?;
         }
+        let side = |value: &str| {
+            value
+                .parse::<usize>()
+                .map_err(|e| ParseError::at(input, value, format!("'{value}' is not a size: {e}")))
+        };
         // println!("Created ground with size {}-{}", grid_width, grid_height);
-        Self {
-            grid_size: (grid_width.parse().unwrap(), grid_height.parse().unwrap()),
+        Ok(Self {
+            grid_size: (side(grid_width)?, side(grid_height)?),
             required_shapes,
-        }
+        })
     }
 }
 
@@ -52,26 +72,35 @@ enum EdgeKind{
     Tooth(u8),
 }
 
-#[derive(PartialEq, Eq)]
-struct Shape {
+#[derive(PartialEq, Eq, Clone)]
+pub struct Shape {
     units: [Unit; 9],
 }
 
 impl Shape {
-    fn from_str(input: &[&str], display_char: char) -> Self {
+    // a shape is drawn on 3 lines of 3 units
+    pub fn from_str(input: &str, display_char: char) -> Result<Self, ParseError> {
         let mut units = [Unit::Empty; 9];
-        let chars = input.iter().flat_map(|i| i.chars());
-        for (n, c) in chars.enumerate() {
-            match c {
-                '#' => units[n] = Unit::Solid(display_char),
-                '.' => units[n] = Unit::Empty,
-                _ => panic!(),
+        let lines: Vec<&str> = input.lines().collect();
+        if lines.len() != 3 {
+            return Err(ParseError::at(input, input, format!("a shape has 3 lines, found {}", lines.len())));
+        }
+        for (y, line) in lines.into_iter().enumerate() {
+            if line.chars().count() != 3 {
+                return Err(ParseError::at(input, line, format!("a shape is 3 units wide, found {}", line.chars().count())));
+            }
+            for (x, (n, c)) in line.char_indices().enumerate() {
+                match c {
+                    '#' => units[y * 3 + x] = Unit::Solid(display_char),
+                    '.' => units[y * 3 + x] = Unit::Empty,
+                    _ => return Err(ParseError::at(input, &line[n..n + c.len_utf8()], format!("'{c}' is not a unit, expected # or ."))),
+                }
             }
         }
-        Self { units }
+        Ok(Self { units })
     }
 
-    fn get_edge<'a>(&'a self, side: EdgeSide) -> EdgeKind {
+    fn get_edge(&self, side: EdgeSide) -> EdgeKind {
         let edge = match side {
             EdgeSide::Top => [&self.units[0], &self.units[1], &self.units[2]],
             EdgeSide::Right => [&self.units[2], &self.units[5], &self.units[8]],
@@ -124,7 +153,7 @@ impl Shape {
     // 2 1 0
     // 5 4 3
     // 8 7 6
-    fn flip_h(&self) -> Self {
+    pub fn flip_h(&self) -> Self {
         let mut new_units = [Unit::Empty; 9];
         new_units[0] = self.units[2];
         new_units[1] = self.units[1];
@@ -142,7 +171,7 @@ impl Shape {
     // 6 7 8
     // 3 4 5
     // 0 1 2
-    fn flip_v(&self) -> Self {
+    pub fn flip_v(&self) -> Self {
         let mut new_units = [Unit::Empty; 9];
         new_units[0] = self.units[6];
         new_units[1] = self.units[7];
@@ -160,7 +189,7 @@ impl Shape {
     // 6 3 0
     // 7 4 1
     // 8 5 2
-    fn rotate_cw(&self) -> Self {
+    pub fn rotate_cw(&self) -> Self {
         let mut new_units = [Unit::Empty; 9];
         new_units[0] = self.units[6];
         new_units[1] = self.units[3];
@@ -178,7 +207,7 @@ impl Shape {
     // 2 5 8
     // 1 4 7
     // 0 3 6
-    fn rotate_ccw(&self) -> Self {
+    pub fn rotate_ccw(&self) -> Self {
         let mut new_units = [Unit::Empty; 9];
         new_units[0] = self.units[2];
         new_units[1] = self.units[5];
@@ -196,7 +225,7 @@ impl Shape {
     // 8 7 6
     // 5 4 3
     // 2 1 0
-    fn rotate_180(&self) -> Self {
+    pub fn rotate_180(&self) -> Self {
         let mut new_units = [Unit::Empty; 9];
         new_units[0] = self.units[8];
         new_units[1] = self.units[7];
@@ -225,7 +254,7 @@ impl Index<(usize, usize)> for Shape {
 
 impl PartialOrd for Shape {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.priority(EdgeSide::Left).partial_cmp(&other.priority(EdgeSide::Left))
+        Some(self.cmp(other))
     }
 }
 // Shapes are ordered based on full solid left edge...
@@ -258,7 +287,7 @@ impl Display for Shape {
         let mut output = "".to_string();
         for (n, unit) in self.units.iter().enumerate() {
             if n % 3 == 0 {
-                output.push_str("\n");
+                output.push('\n');
             }
             match unit {
                 Unit::Solid(c) => output = format!("{output}{}", c),
@@ -308,7 +337,7 @@ impl Grid {
         [
             &self[(pos.0, pos.1)],
             &self[(pos.0 + 1, pos.1)],
-            &&self[(pos.0 + 2, pos.1)],
+            &self[(pos.0 + 2, pos.1)],
             &self[(pos.0, pos.1 + 1)],
             &self[(pos.0 + 1, pos.1 + 1)],
             &self[(pos.0 + 2, pos.1 + 1)],
@@ -346,7 +375,7 @@ impl Grid {
             let shape_pos = ((n % 3), (n / 3));
             let grid_pos = (shape_pos.0 + at_pos.0, shape_pos.1 + at_pos.1);
             if let Unit::Solid(_) = unit {
-                self[grid_pos] = unit.clone();
+                self[grid_pos] = *unit;
             }
         }
     }
@@ -357,7 +386,7 @@ impl Display for Grid {
         let mut output = "".to_string();
         for (n, unit) in self.units.iter().enumerate() {
             if n % self.width == 0 && n != 0 {
-                output.push_str("\n");
+                output.push('\n');
             }
             match unit {
                 Unit::Solid(c) => output = format!("{output}{c}"),
@@ -419,20 +448,20 @@ impl Candidates {
     }
 
     fn check_space_compatibility(shape: &Shape, space: [&Unit; 9]) -> bool {
-        for n in 0..9 {
-            if let Unit::Solid(_ ) = shape.units[n] && let Unit::Solid(_) = space[n] {
+        for (unit, space_unit) in shape.units.iter().zip(space) {
+            if let Unit::Solid(_) = unit && let Unit::Solid(_) = space_unit {
                 return false;
             }
         }
-        return true;
+        true
     }
 
     fn get(&mut self, space: [&Unit; 9]) -> Option<&Shape> {
         let mut chosen_one = None;
-        for (id, shape) in self.shapes.iter().filter(|(id, _)| self.counter[*id as usize] > 0) {
-            if Self::check_space_compatibility(&shape, space) {
+        for (id, shape) in self.shapes.iter().filter(|(id, _)| self.counter[*id] > 0) {
+            if Self::check_space_compatibility(shape, space) {
                 chosen_one = Some(shape);
-                self.counter[*id as usize] -= 1;
+                self.counter[*id] -= 1;
                 break;
             }
         }
@@ -442,7 +471,8 @@ impl Candidates {
 
 // get the unit_position based on chosen sort function
 fn get_next_unit(from_grid: &Grid, exclude: &[(usize, usize)]) -> Option<(usize, usize)> {
-    let next_unit_id = from_grid
+    
+    from_grid
         .get_units_by_nb_priority()
         .iter()
         .copied()
@@ -451,24 +481,41 @@ fn get_next_unit(from_grid: &Grid, exclude: &[(usize, usize)]) -> Option<(usize,
         } else {
             None
         })
-        .next();
-    next_unit_id
+        .next()
 }
 
-pub fn part_one() {
-    let (problems, shapes) = parse(InputMode::Normal);
-    let mut candidates = Candidates::new(shapes);
+pub struct Solver;
+
+impl Solution for Solver {
+    type Model = (Vec<Problem>, Vec<Shape>);
+
+    fn parse(input: &str) -> Result<Self::Model, ParseError> {
+        parse(input)
+    }
+
+    fn part_one((problems, shapes): &Self::Model) -> Answer {
+        grounds_complete(problems, shapes).into()
+    }
+
+    // the last day has a single puzzle
+    fn part_two(_model: &Self::Model) -> Answer {
+        Answer::Unsolved
+    }
+}
+
+pub fn grounds_complete(problems: &[Problem], shapes: &[Shape]) -> u32 {
+    let mut candidates = Candidates::new(shapes.to_vec());
     // the output
     let mut ground_complete = 0;
 
 
-    for (p_id, problem) in problems.into_iter().enumerate() {
+    for problem in problems.iter() {
         // testing ground
         candidates.reset_counter(problem.required_shapes);
         let (grid_width, grid_height) = problem.grid_size;
         let mut grid = Grid::new_empty(grid_width, grid_height);
         // println!(
-        //     "Problem {p_id}: {}-{}, shapes required: {:?}",
+        //     "Problem: {}-{}, shapes required: {:?}",
         //     problem.grid_size.0, problem.grid_size.1, problem.required_shapes
         // );
 
@@ -495,35 +542,73 @@ pub fn part_one() {
         }
         if candidates.left() == 0 {
             ground_complete += 1;
-            println!("Ground {p_id} can fit all presents!");
-        } else {
-            println!("Ground {p_id} can't. Left items: {:?}", candidates.counter);
         }
     }
 
-    println!("Grounds complete: {ground_complete}");
+    ground_complete
 }
 
-fn parse(mode: InputMode) -> (Vec<Problem>, Vec<Shape>) {
-    let input: String;
-    match mode {
-        InputMode::Example => {
-            input = ""
-                .to_string();
-        }
-        InputMode::Normal => input = super::load_input("input/input-day12"),
-    }
-    let mut grounds: Vec<Problem> = vec![];
+pub fn parse(input: &str) -> Result<(Vec<Problem>, Vec<Shape>), ParseError> {
     let mut shapes: Vec<Shape> = vec![];
-    let mut blocks: Vec<&str> = input.split("\n\n").collect();
-    let grounds_str = blocks.pop().unwrap().lines().collect::<Vec<&str>>();
+    let mut blocks: Vec<&str> = input.trim_end().split("\n\n").collect();
+    let grounds_str = blocks.pop().unwrap_or_default();
     let block_visuals = ['A', 'B', 'C', 'D', 'E', 'F'];
+    if blocks.len() != block_visuals.len() {
+        return Err(ParseError::at(input, grounds_str, format!("expected {} shapes before the regions, found {}", block_visuals.len(), blocks.len())));
+    }
     for (n, shape) in blocks.iter().enumerate() {
-        let s_line: Vec<&str> = shape.lines().collect();
-        shapes.push(Shape::from_str(&s_line[1..], block_visuals[n]));
+        // skip the "<index>:" header
+        let (header, units) = shape.split_once("\n").unwrap_or((shape, ""));
+        if header.trim() != format!("{n}:") {
+            return Err(ParseError::at(input, header, format!("expected the header of shape {n}:")));
+        }
+        shapes.push(Shape::from_str(units, block_visuals[n]).map_err(|e| e.within(input, units))?);
     }
-    for g in grounds_str {
-        grounds.push(Problem::from_str(g));
+    let grounds = parse_lines(grounds_str, Problem::from_str).map_err(|e| e.within(input, grounds_str))?;
+    Ok((grounds, shapes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regions() {
+        let test_input = "0:
+###
+##.
+##.
+
+1:
+###
+##.
+.##
+
+2:
+.##
+###
+##.
+
+3:
+##.
+###
+##.
+
+4:
+###
+#..
+###
+
+5:
+###
+.#.
+###
+
+4x4: 0 0 0 0 2 0
+12x5: 1 0 1 0 2 2
+12x5: 1 0 1 0 3 2";
+        let model = Solver::parse(test_input).unwrap();
+        assert_eq!(Solver::part_one(&model), Answer::Number(2));
+        assert_eq!(Solver::part_two(&model), Answer::Unsolved);
     }
-    (grounds, shapes)
 }
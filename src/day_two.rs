@@ -1,114 +1,694 @@
 #![allow(dead_code)]
 
-pub fn part_one() {
-    println!("Hello Day Two - part one");
-    let mut output = 0usize;
-    let input = super::load_input("input/input-day2");
-    let ranges: Vec<&str> = input.trim().split(",").collect();
-    for range in ranges {
-        let n = IdRange::from_str(range);
-        output += n.find_repetitions_part_one().iter().sum::<usize>();
+use std::collections::BTreeSet;
+use std::fmt::Display;
+use std::io::{self, Write};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use crate::parse::ParseError;
+use crate::solution::{Answer, Solution, TraceFormat};
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Model = IdRanges;
+
+    fn parse(input: &str) -> Result<Self::Model, ParseError> {
+        IdRanges::from_input(input)
+    }
+
+    fn part_one(ranges: &Self::Model) -> Answer {
+        let mut output = IdSum::default();
+        for range in &ranges.ranges {
+            output.add_sum(&range.repeated_total(&Rule::part_one()).1);
+        }
+        output.into()
+    }
+
+    fn part_two(ranges: &Self::Model) -> Answer {
+        let mut output = IdSum::default();
+        for range in &ranges.ranges {
+            output.add_sum(&range.repeated_total(&Rule::part_two()).1);
+        }
+        output.into()
+    }
+
+    // the invalid IDs found in each range
+    fn trace(ranges: &Self::Model, format: TraceFormat, out: &mut dyn Write) -> io::Result<bool> {
+        let reports = [(1, Rule::part_one()), (2, Rule::part_two())]
+            .map(|(part, rule)| (part, range_reports(ranges, &rule, REPORT_EXAMPLES)));
+        if format == TraceFormat::Csv {
+            writeln!(out, "range,merged,part,count,sum,examples")?;
+        }
+        for (part, reports) in reports {
+            for report in reports {
+                match format {
+                    TraceFormat::Text | TraceFormat::Color => writeln!(out, "part {part} - {report}")?,
+                    TraceFormat::Csv => {
+                        let examples: Vec<String> = report.examples.iter().map(Id::to_string).collect();
+                        writeln!(
+                            out,
+                            "{},{},{part},{},{},{}",
+                            report.range,
+                            report.merged,
+                            report.count,
+                            report.sum,
+                            examples.join(" ")
+                        )?
+                    }
+                }
+            }
+        }
+        Ok(true)
     }
-    println!("Result: {output}");
 }
 
-pub fn part_two() {
-    println!("Hello Day Two - part two");
-    let mut output = 0usize;
-    let input = super::load_input("input/input-day2");
-    let ranges: Vec<&str> = input.trim().split(",").collect();
-    for range in ranges {
-        let n = IdRange::from_str(range);
-        output += n.find_repetitions_part_two();
+// invalid IDs listed in a range report
+const REPORT_EXAMPLES: usize = 3;
+
+/// How many times the block of an invalid ID is repeated,
+/// a block is always repeated at least twice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Repetitions {
+    Exactly(u32),
+    AtLeast(u32),
+    AtMost(u32),
+}
+
+impl Repetitions {
+    pub fn accepts(&self, count: u32) -> bool {
+        count >= 2
+            && match *self {
+                Repetitions::Exactly(k) => count == k,
+                Repetitions::AtLeast(k) => count >= k,
+                Repetitions::AtMost(k) => count <= k,
+            }
     }
-    println!("Result: {output}");
 }
 
-struct IdRange {
-    start: usize,
-    end: usize,
+/// Which IDs are invalid: the ones written in `base`
+/// as a block of digits repeated some times.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rule {
+    repetitions: Repetitions,
+    base: u32,
+}
+
+impl Rule {
+    pub fn new(repetitions: Repetitions, base: u32) -> Self {
+        assert!((2..=36).contains(&base), "base {base} is not between 2 and 36");
+        Self { repetitions, base }
+    }
+
+    // a block repeated twice
+    pub fn part_one() -> Self {
+        Self::new(Repetitions::Exactly(2), 10)
+    }
+
+    // a block repeated any number of times
+    pub fn part_two() -> Self {
+        Self::new(Repetitions::AtLeast(2), 10)
+    }
+
+    pub fn repetitions(&self) -> Repetitions {
+        self.repetitions
+    }
+
+    pub fn base(&self) -> u32 {
+        self.base
+    }
+}
+
+/// IDs can be longer than the puzzle ones, up to 38 decimal digits.
+pub type Id = u128;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdRange {
+    pub start: Id,
+    pub end: Id,
+}
+
+impl Display for IdRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+impl FromStr for IdRange {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (l, r) = input
+            .split_once("-")
+            .ok_or_else(|| ParseError::at(input, input, format!("'{input}' is not a range, expected <start>-<end>")))?;
+        let parse_id = |id: &str| {
+            id.parse::<Id>()
+                .map_err(|e| ParseError::at(input, id, format!("'{id}' is not an ID: {e}")))
+        };
+        let (start, end) = (parse_id(l)?, parse_id(r)?);
+        if start > end {
+            return Err(ParseError::at(input, input, format!("range {start}-{end} ends before it starts")));
+        }
+        Ok(Self { start, end })
+    }
 }
 
 impl IdRange {
-    fn from_str(input: &str) -> Self {
-        let (l, r) = input.split_once("-").unwrap();
-        let (start, end) = (l.parse::<usize>().unwrap(), r.parse::<usize>().unwrap());
-        Self { start, end }
+    /// The first `limit` IDs of the range breaking the rule, in increasing order.
+    pub fn repeated_ids(&self, rule: &Rule, limit: usize) -> Vec<Id> {
+        let base = rule.base as Id;
+        let mut ids = vec![];
+        for length in digits(self.start, base)..=digits(self.end, base) {
+            // an ID like 111111 is found as 1, 11 and 111 repeated: the
+            // smallest IDs of the length are among the first of each size
+            let mut found = BTreeSet::new();
+            for count in accepted_counts(rule, length) {
+                if let Some((multiplier, blocks)) = self.blocks(length, length / count, base) {
+                    found.extend(blocks.take(limit).map(|block| block * multiplier));
+                }
+            }
+            ids.extend(found.into_iter().take(limit - ids.len()));
+            if ids.len() == limit {
+                break;
+            }
+        }
+        ids
     }
 
-    fn find_repetitions_part_one(&self) -> Vec<usize> {
-        let mut output = vec![];
-        for x in self.start..=self.end {
-            let x_string = x.to_string();
-            let (a, b) = x_string.split_at(x_string.len() / 2);
-            if a == b {
-                output.push(format!("{a}{b}").parse::<usize>().unwrap());
+    /// How many IDs of the range break the rule, and their sum.
+    ///
+    /// The IDs are not searched one by one: a block `b` of `size` digits
+    /// repeated `count` times is `b * 10..010..01` (in the rule base), so
+    /// the IDs of a size are an arithmetic series. An ID repeating a
+    /// smaller block is in several series, it's counted once by keeping
+    /// for each ID length the IDs by their smallest block: by inclusion
+    /// and exclusion over the divisors, with the Möbius function.
+    pub fn repeated_total(&self, rule: &Rule) -> (u128, IdSum) {
+        let base = rule.base as Id;
+        let (mut count, mut sum, mut excluded) = (0i128, IdSum::default(), IdSum::default());
+        for length in digits(self.start, base)..=digits(self.end, base) {
+            let counts = accepted_counts(rule, length);
+            let sizes: Vec<u32> = (1..length).filter(|size| length.is_multiple_of(*size)).collect();
+            // the IDs with a smallest block of `size` digits are invalid
+            // when a count of repetitions of that block is accepted
+            let invalid = |size: u32| counts.iter().any(|c| (length / size).is_multiple_of(*c));
+            for &size in &sizes {
+                // the weight of the series of `size` in the IDs by smallest block
+                let weight: i32 = sizes
+                    .iter()
+                    .filter(|&&smallest| smallest.is_multiple_of(size) && invalid(smallest))
+                    .map(|smallest| mobius(smallest / size))
+                    .sum();
+                let Some((multiplier, blocks)) = self.blocks(length, size, base).filter(|_| weight != 0) else {
+                    continue;
+                };
+                let (first, last) = (*blocks.start(), *blocks.end());
+                let n = last - first + 1;
+                // n (first + last) / 2, one of the two factors is even
+                let (a, b) = if n % 2 == 0 { (n / 2, first + last) } else { (n, (first + last) / 2) };
+                let series = IdSum::from(multiplier).mul(a).mul(b).mul(weight.unsigned_abs() as Id);
+                count += weight as i128 * n as i128;
+                if weight > 0 {
+                    sum.add_sum(&series);
+                } else {
+                    excluded.add_sum(&series);
+                }
             }
         }
-        output
+        sum.sub_sum(&excluded);
+        (count as u128, sum)
     }
 
-    fn find_repetitions_part_two(&self) -> usize {
-        let mut output = 0usize;
-        for x in self.start..=self.end {
-            let n = check_id(x.to_string());
-            output += n.iter().sum::<usize>();
+    // the blocks of `size` digits repeated to make the IDs of the range
+    // with `length` digits, and the multiplier 10..010..01 giving those IDs
+    fn blocks(&self, length: u32, size: u32, base: Id) -> Option<(Id, RangeInclusive<Id>)> {
+        let count = length / size;
+        // the largest lengths can't be written in an Id
+        let multiplier = (0..count).try_fold(0 as Id, |m, n| m.checked_add(base.checked_pow(size * n)?))?;
+        let block_end = base.checked_pow(size)?;
+        let first_block = base.pow(size - 1).max(self.start.div_ceil(multiplier));
+        let last_block = (block_end - 1).min(self.end / multiplier);
+        (first_block <= last_block).then_some((multiplier, first_block..=last_block))
+    }
+}
+
+// the accepted counts of repetitions of a block making an ID of `length` digits
+fn accepted_counts(rule: &Rule, length: u32) -> Vec<u32> {
+    (2..=length).filter(|count| length.is_multiple_of(*count) && rule.repetitions.accepts(*count)).collect()
+}
+
+fn mobius(mut n: u32) -> i32 {
+    let mut output = 1;
+    let mut factor = 2;
+    while factor * factor <= n {
+        if n.is_multiple_of(factor) {
+            n /= factor;
+            if n.is_multiple_of(factor) {
+                return 0;
+            }
+            output = -output;
+        }
+        factor += 1;
+    }
+    if n > 1 { -output } else { output }
+}
+
+/// The ranges of IDs to check.
+///
+/// The input is a comma separated list of ranges `start-end`. When it
+/// starts with a line `merge`, the overlapping ranges are merged first
+/// so that an ID in several ranges is only counted once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdRanges {
+    pub ranges: Vec<IdRange>,
+    /// whether the ranges were merged
+    pub merged: bool,
+    /// the number of input ranges making up each range
+    pub sources: Vec<usize>,
+}
+
+impl IdRanges {
+    pub fn from_input(input: &str) -> Result<Self, ParseError> {
+        let (merged, list) = match input.trim_start().split_once('\n') {
+            Some((first, list)) if first.trim() == "merge" => (true, list),
+            _ => (false, input),
+        };
+        let ranges: Vec<IdRange> = list
+            .trim()
+            .split(",")
+            .map(|range| IdRange::from_str(range).map_err(|e| e.within(input, range)))
+            .collect::<Result<_, _>>()?;
+        let (ranges, sources) = if merged {
+            merge_ranges_counted(&ranges).into_iter().unzip()
+        } else {
+            let sources = vec![1; ranges.len()];
+            (ranges, sources)
+        };
+        Ok(Self { ranges, merged, sources })
+    }
+}
+
+/// Sort the ranges and merge the overlapping or adjacent ones,
+/// so that no ID is counted twice.
+pub fn merge_ranges(ranges: &[IdRange]) -> Vec<IdRange> {
+    merge_ranges_counted(ranges).into_iter().map(|(range, _)| range).collect()
+}
+
+// the merged ranges with the number of input ranges in each
+fn merge_ranges_counted(ranges: &[IdRange]) -> Vec<(IdRange, usize)> {
+    let mut sorted = ranges.to_vec();
+    sorted.sort_by_key(|r| (r.start, r.end));
+    let mut merged: Vec<(IdRange, usize)> = vec![];
+    for range in sorted {
+        match merged.last_mut() {
+            Some((last, sources)) if range.start <= last.end.saturating_add(1) => {
+                last.end = last.end.max(range.end);
+                *sources += 1;
+            }
+            _ => merged.push((range, 1)),
+        }
+    }
+    merged
+}
+
+/// The invalid IDs of a range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeReport {
+    pub range: IdRange,
+    /// the range absorbed other ranges of the input
+    pub merged: bool,
+    pub count: u128,
+    pub sum: IdSum,
+    /// the first invalid IDs
+    pub examples: Vec<Id>,
+}
+
+impl Display for RangeReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let merged = if self.merged { " (merged)" } else { "" };
+        write!(f, "{}{merged}: {} invalid IDs, sum {}", self.range, self.count, self.sum)?;
+        if !self.examples.is_empty() {
+            let examples: Vec<String> = self.examples.iter().map(Id::to_string).collect();
+            let more = if self.count > self.examples.len() as u128 { ", ..." } else { "" };
+            write!(f, " ({}{more})", examples.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+pub fn range_reports(ranges: &IdRanges, rule: &Rule, examples: usize) -> Vec<RangeReport> {
+    ranges
+        .ranges
+        .iter()
+        .zip(&ranges.sources)
+        .map(|(range, sources)| {
+            let (count, sum) = range.repeated_total(rule);
+            RangeReport {
+                range: *range,
+                merged: *sources > 1,
+                count,
+                sum,
+                examples: range.repeated_ids(rule, examples),
+            }
+        })
+        .collect()
+}
+
+/// A sum of IDs that can't overflow, stored as 64 bits limbs
+/// from the least significant one.
+#[derive(Debug, Clone, Default, Eq)]
+pub struct IdSum {
+    limbs: Vec<u64>,
+}
+
+impl IdSum {
+    pub fn add(&mut self, id: Id) {
+        self.add_at(0, id);
+    }
+
+    // add `value` times the `n`-th limb unit
+    fn add_at(&mut self, mut n: usize, value: Id) {
+        let mut carry = value;
+        while carry != 0 {
+            if n >= self.limbs.len() {
+                self.limbs.resize(n + 1, 0);
+            }
+            let total = self.limbs[n] as u128 + (carry as u64) as u128;
+            self.limbs[n] = total as u64;
+            carry = (carry >> 64) + (total >> 64);
+            n += 1;
+        }
+    }
+
+    pub fn add_sum(&mut self, other: &IdSum) {
+        for (n, limb) in other.limbs.iter().enumerate() {
+            self.add_at(n, *limb as Id);
+        }
+    }
+
+    /// Subtract a sum that is not larger.
+    pub fn sub_sum(&mut self, other: &IdSum) {
+        let other = other.significant_limbs();
+        assert!(other.len() <= self.limbs.len(), "subtracting a larger sum");
+        let mut borrow = false;
+        for n in 0..self.limbs.len() {
+            let (limb, below) = self.limbs[n].overflowing_sub(other.get(n).copied().unwrap_or(0));
+            let (limb, below_borrow) = limb.overflowing_sub(borrow as u64);
+            self.limbs[n] = limb;
+            borrow = below || below_borrow;
+        }
+        assert!(!borrow, "subtracting a larger sum");
+    }
+
+    pub fn mul(&self, factor: Id) -> IdSum {
+        let mut output = IdSum::default();
+        for (n, limb) in self.limbs.iter().enumerate() {
+            // the two halves of the factor, each product fits in an Id
+            output.add_at(n, *limb as Id * (factor as u64) as Id);
+            output.add_at(n + 1, *limb as Id * (factor >> 64));
         }
         output
     }
 
+    /// The sum, when it fits in a u64.
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.significant_limbs() {
+            [] => Some(0),
+            [low] => Some(*low),
+            _ => None,
+        }
+    }
 
+    fn significant_limbs(&self) -> &[u64] {
+        let len = self.limbs.iter().rposition(|l| *l != 0).map_or(0, |n| n + 1);
+        &self.limbs[..len]
+    }
 }
 
-fn check_id(id: String) -> Vec<usize>{
+// the high limbs left at zero by a subtraction don't count
+impl PartialEq for IdSum {
+    fn eq(&self, other: &Self) -> bool {
+        self.significant_limbs() == other.significant_limbs()
+    }
+}
 
-    // println!("Checking ID {id}");
-    let mut output = vec![];
-    for i in (1 ..=id.len()/2 ).rev() {
-        let chunks = id.chars()
-            .collect::<Vec<char>>()
-            .chunks(i)
-            .map(|chunk| chunk.iter().collect::<String>())
-            .collect::<Vec<String>>();
-        let comparison = chunks.first().unwrap();
-        // println!("..substr to compare: {}", comparison);
-        let mut condition = true;
-            for chunk in chunks.iter() {
-                if chunk != comparison {
-                    condition = false;
-                    break;
+impl From<Id> for IdSum {
+    fn from(id: Id) -> Self {
+        let mut sum = Self::default();
+        sum.add(id);
+        sum
+    }
+}
+
+impl Extend<Id> for IdSum {
+    fn extend<T: IntoIterator<Item = Id>>(&mut self, ids: T) {
+        for id in ids {
+            self.add(id);
+        }
+    }
+}
+
+impl FromIterator<Id> for IdSum {
+    fn from_iter<T: IntoIterator<Item = Id>>(ids: T) -> Self {
+        let mut sum = Self::default();
+        sum.extend(ids);
+        sum
+    }
+}
+
+impl Display for IdSum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // split in chunks of 19 decimal digits, dividing the limbs by 10^19
+        const CHUNK: u128 = 10_000_000_000_000_000_000;
+        let mut limbs = self.significant_limbs().to_vec();
+        let mut chunks = vec![];
+        while !limbs.is_empty() {
+            let mut rest = 0u128;
+            for limb in limbs.iter_mut().rev() {
+                let value = (rest << 64) | *limb as u128;
+                *limb = (value / CHUNK) as u64;
+                rest = value % CHUNK;
+            }
+            chunks.push(rest as u64);
+            while limbs.last() == Some(&0) {
+                limbs.pop();
+            }
+        }
+        match chunks.split_last() {
+            None => write!(f, "0"),
+            Some((first, rest)) => {
+                write!(f, "{first}")?;
+                for chunk in rest.iter().rev() {
+                    write!(f, "{chunk:019}")?;
                 }
+                Ok(())
             }
-        if condition {
-            let new_found = comparison.repeat(chunks.len()).parse::<usize>().unwrap();
-            // println!("...found repetition: {}", new_found);
-            output.push(new_found);
-            break;
         }
     }
-    output
+}
+
+// sums too large for a number are given as text
+impl From<IdSum> for Answer {
+    fn from(sum: IdSum) -> Self {
+        match sum.to_u64() {
+            Some(n) => Answer::Number(n),
+            None => Answer::Text(sum.to_string()),
+        }
+    }
+}
+
+fn digits(n: Id, base: Id) -> u32 {
+    n.checked_ilog(base).unwrap_or(0) + 1
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::XorShift;
+
+    // the scans of every ID of a range, checking the results of the enumeration
+    fn find_repetitions_part_one(range: &IdRange) -> Vec<Id> {
+        let mut output = vec![];
+        for x in range.start..=range.end {
+            let x_string = x.to_string();
+            let (a, b) = x_string.split_at(x_string.len() / 2);
+            if a == b {
+                output.push(format!("{a}{b}").parse::<Id>().unwrap());
+            }
+        }
+        output
+    }
+
+    fn find_repetitions_part_two(range: &IdRange) -> Id {
+        let mut output: Id = 0;
+        for x in range.start..=range.end {
+            let n = check_id(x.to_string());
+            output += n.iter().sum::<Id>();
+        }
+        output
+    }
+
+    fn check_id(id: String) -> Vec<Id> {
+        let mut output = vec![];
+        for i in (1..=id.len() / 2).rev() {
+            let chunks = id
+                .chars()
+                .collect::<Vec<char>>()
+                .chunks(i)
+                .map(|chunk| chunk.iter().collect::<String>())
+                .collect::<Vec<String>>();
+            let comparison = chunks.first().unwrap();
+            if chunks.iter().all(|chunk| chunk == comparison) {
+                output.push(comparison.repeat(chunks.len()).parse::<Id>().unwrap());
+                break;
+            }
+        }
+        output
+    }
 
     #[test]
     fn reps() {
-        let test_input = "";
+        let test_input = "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,\
+1698522-1698528,446443-446449,38593856-38593862,565653-565659,\
+824824821-824824827,2121212118-2121212124";
         // let test_input = "222220-222224";
-        let ranges: Vec<&str> = test_input.trim().split(",").collect();
-        let mut output = 0usize;
-        for range in ranges {
-            let n = IdRange::from_str(range);
-            output += n.find_repetitions_part_two();
-        }
-        println!("Output: {output}");
+        let ranges = Solver::parse(test_input).unwrap();
+        assert_eq!(Solver::part_one(&ranges), Answer::Number(1227775554));
+        assert_eq!(Solver::part_two(&ranges), Answer::Number(4174379265));
     }
 
     #[test]
     fn single() {
         let test_id = "1212".to_string();
-        check_id(test_id);
+        assert_eq!(check_id(test_id), vec![1212]);
+        assert!(check_id("1213".to_string()).is_empty());
+    }
+
+    #[test]
+    fn multipliers() {
+        let range = IdRange { start: 1, end: 1_000_000 };
+        assert_eq!(range.repeated_ids(&Rule::part_one(), 3), vec![11, 22, 33]);
+        assert_eq!(range.repeated_total(&Rule::part_one()).0, 9 + 90 + 900);
+        // 111111 is 1 x6, 11 x3 and 111 x2 but it's counted once
+        let any = range.repeated_ids(&Rule::part_two(), usize::MAX);
+        assert_eq!(any.iter().filter(|id| **id == 111111).count(), 1);
+        assert_eq!(range.repeated_total(&Rule::part_two()).0, any.len() as u128);
+    }
+
+    #[test]
+    fn rules() {
+        let range = IdRange { start: 1, end: 20 };
+        let binary = |repetitions| range.repeated_ids(&Rule::new(repetitions, 2), usize::MAX);
+        // 11, 111, 1010, 1111
+        assert_eq!(binary(Repetitions::Exactly(2)), vec![3, 10, 15]);
+        assert_eq!(binary(Repetitions::AtLeast(3)), vec![7, 15]);
+        assert_eq!(binary(Repetitions::AtMost(3)), vec![3, 7, 10, 15]);
+        assert_eq!(binary(Repetitions::Exactly(4)), vec![15]);
+
+        let range = IdRange { start: 0x1200, end: 0x12ff };
+        assert_eq!(range.repeated_ids(&Rule::new(Repetitions::Exactly(2), 16), usize::MAX), vec![0x1212]);
+        let range = IdRange { start: 0, end: Id::MAX };
+        assert_eq!(range.repeated_ids(&Rule::new(Repetitions::Exactly(36), 36), usize::MAX).len(), 0);
+        assert_eq!(range.repeated_ids(&Rule::new(Repetitions::Exactly(128), 2), usize::MAX), vec![Id::MAX]);
+        assert_eq!(range.repeated_total(&Rule::new(Repetitions::Exactly(128), 2)), (1, Id::MAX.into()));
+    }
+
+    #[test]
+    fn enumeration_matches_search() {
+        for (start, end) in [(1, 5000), (95, 115), (998, 1012), (222220, 222224), (1188511880, 1188511890)] {
+            let range = IdRange { start, end };
+            assert_eq!(range.repeated_ids(&Rule::part_one(), usize::MAX), find_repetitions_part_one(&range));
+            assert_eq!(
+                range.repeated_ids(&Rule::part_two(), usize::MAX).iter().sum::<Id>(),
+                find_repetitions_part_two(&range)
+            );
+        }
+    }
+
+    #[test]
+    fn totals_match_enumeration() {
+        let mut random = XorShift::new(0x2025_0002);
+        for _ in 0..300 {
+            let base = random.below(15) as u32 + 2;
+            let (start_bits, len_bits) = (random.below(22), random.below(22));
+            let start = random.below(1 << start_bits) as Id;
+            let range = IdRange { start, end: start + random.below(1 << len_bits) as Id };
+            let k = random.below(6) as u32 + 2;
+            let repetitions = match random.below(3) {
+                0 => Repetitions::Exactly(k),
+                1 => Repetitions::AtLeast(k),
+                _ => Repetitions::AtMost(k),
+            };
+            let rule = Rule::new(repetitions, base);
+            let ids = range.repeated_ids(&rule, usize::MAX);
+            let total = (ids.len() as u128, ids.iter().copied().collect());
+            assert_eq!(range.repeated_total(&rule), total, "{range} {rule:?}");
+        }
+        // every ID up to 20 digits
+        let ranges = Solver::parse("1-99999999999999999999").unwrap();
+        assert_eq!(Solver::part_one(&ranges), Answer::Text("495495495500040950035950040950".to_string()));
+        assert_eq!(range_reports(&ranges, &Rule::part_two(), 3)[0].examples, vec![11, 22, 33]);
+    }
+
+    #[test]
+    fn merge() {
+        let ranges = Solver::parse("95-115,11-22,20-30,31-40,998-1012,90-100").unwrap().ranges;
+        let merged = merge_ranges(&ranges);
+        assert_eq!(
+            merged,
+            vec![IdRange { start: 11, end: 40 }, IdRange { start: 90, end: 115 }, IdRange { start: 998, end: 1012 }]
+        );
+        // 22 and 99 are in two ranges each
+        let sum = |ranges: &[IdRange]| -> Id {
+            ranges.iter().map(|r| r.repeated_ids(&Rule::part_one(), usize::MAX).iter().sum::<Id>()).sum()
+        };
+        assert_eq!(sum(&ranges) - sum(&merged), 22 + 99);
+
+        let input = "95-115,11-22,20-30,31-40,998-1012,90-100";
+        let separate = Solver::parse(input).unwrap();
+        let merged = Solver::parse(&format!("merge\n{input}")).unwrap();
+        assert!(merged.merged && !separate.merged);
+        assert_eq!(merged.ranges.len(), 3);
+        assert_eq!(merged.sources, vec![3, 2, 1]);
+        assert_eq!(separate.sources, vec![1; 6]);
+        assert_eq!(Solver::part_one(&separate), Answer::Number(11 + 22 + 22 + 33 + 99 + 99 + 1010));
+        assert_eq!(Solver::part_one(&merged), Answer::Number(11 + 22 + 33 + 99 + 1010));
+    }
+
+    #[test]
+    fn report() {
+        let ranges = Solver::parse("11-22,95-115,1-9").unwrap();
+        let reports = range_reports(&ranges, &Rule::part_two(), 1);
+        assert_eq!(reports[0].to_string(), "11-22: 2 invalid IDs, sum 33 (11, ...)");
+        assert_eq!(reports[1].to_string(), "95-115: 2 invalid IDs, sum 210 (99, ...)");
+        assert_eq!(reports[2].to_string(), "1-9: 0 invalid IDs, sum 0");
+        let ranges = Solver::parse("merge\n11-22,20-33,95-115").unwrap();
+        let reports = range_reports(&ranges, &Rule::part_one(), 3);
+        assert_eq!(reports[0].to_string(), "11-33 (merged): 3 invalid IDs, sum 66 (11, 22, 33)");
+        assert_eq!(reports[1].to_string(), "95-115: 1 invalid IDs, sum 99 (99)");
+    }
+
+    #[test]
+    fn large_sums() {
+        let sum: IdSum = [Id::MAX, Id::MAX, 1].into_iter().collect();
+        assert_eq!(sum.to_string(), "680564733841876926926749214863536422911");
+        assert_eq!(IdSum::default().to_string(), "0");
+        let sum: IdSum = [u64::MAX as Id, 1].into_iter().collect();
+        assert_eq!(sum.to_string(), "18446744073709551616");
+        assert_eq!(sum.to_u64(), None);
+
+        // two IDs of 38 digits, past the u64 range
+        let ranges = Solver::parse("10000000000000000001000000000000000000-10000000000000000011000000000000000001").unwrap();
+        assert_eq!(
+            Solver::part_one(&ranges),
+            Answer::Text("20000000000000000012000000000000000001".to_string())
+        );
+    }
+
+    #[test]
+    fn wrong_range() {
+        let error = Solver::parse("11-22,95-1x5\n").err().unwrap();
+        assert_eq!((error.line, error.column), (1, 10));
     }
 }
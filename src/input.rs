@@ -0,0 +1,75 @@
+use std::{fs, io::{self, Read}, path::{Path, PathBuf}};
+
+use anyhow::{Context, Result, bail};
+
+pub const INPUT_DIR: &str = "input";
+/// The input path standing for the standard input.
+pub const STDIN: &str = "-";
+
+/// Which input file of a day to use:
+/// the puzzle input `input/input-dayN` or the example `input/example-dayN`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InputMode {
+    Example,
+    #[default]
+    Normal,
+}
+
+/// Path of the input file for a day part.
+///
+/// Some days have a different example for each part,
+/// stored as `example-dayN-partP`: it takes precedence over `example-dayN`.
+pub fn input_path(day: u8, part: u8, mode: InputMode) -> PathBuf {
+    let dir = Path::new(INPUT_DIR);
+    match mode {
+        InputMode::Normal => dir.join(format!("input-day{day}")),
+        InputMode::Example => {
+            let part_example = dir.join(format!("example-day{day}-part{part}"));
+            if part_example.exists() {
+                part_example
+            } else {
+                dir.join(format!("example-day{day}"))
+            }
+        }
+    }
+}
+
+pub fn load_input(path: &Path) -> Result<String> {
+    if path == Path::new(STDIN) {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input).context("can't read the input from stdin")?;
+        return Ok(input);
+    }
+    if !path.exists() {
+        bail!("input file {} not found", path.display());
+    }
+    fs::read_to_string(path).with_context(|| format!("can't read input file {}", path.display()))
+}
+
+/// How an input path is named in the messages.
+pub fn input_name(path: &Path) -> String {
+    if path == Path::new(STDIN) {
+        "stdin".to_string()
+    } else {
+        path.display().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example_paths() {
+        assert_eq!(input_path(3, 1, InputMode::Normal), Path::new("input/input-day3"));
+        assert_eq!(input_path(3, 2, InputMode::Example), Path::new("input/example-day3"));
+        assert_eq!(input_path(11, 1, InputMode::Example), Path::new("input/example-day11"));
+        assert_eq!(input_path(11, 2, InputMode::Example), Path::new("input/example-day11-part2"));
+    }
+
+    #[test]
+    fn missing_input() {
+        let error = load_input(Path::new("input/input-day99")).unwrap_err();
+        assert_eq!(error.to_string(), "input file input/input-day99 not found");
+    }
+}
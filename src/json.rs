@@ -0,0 +1,149 @@
+use std::fmt::Display;
+
+use aoc_2025::answers::Status;
+use aoc_2025::bench::{BenchReport, Phase};
+use aoc_2025::runner::PartReport;
+use aoc_2025::solution::Answer;
+
+/// A JSON value, just what the reports need.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Number(u64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(&'static str, Json)>),
+}
+
+impl Display for Json {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Number(n) => write!(f, "{n}"),
+            Json::String(s) => write_string(f, s),
+            Json::Array(values) => {
+                write!(f, "[")?;
+                for (n, value) in values.iter().enumerate() {
+                    if n > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{value}")?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(fields) => {
+                write!(f, "{{")?;
+                for (n, (key, value)) in fields.iter().enumerate() {
+                    if n > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{value}")?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut std::fmt::Formatter<'_>, s: &str) -> std::fmt::Result {
+    write!(f, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{c}")?,
+        }
+    }
+    write!(f, "\"")
+}
+
+impl From<&Answer> for Json {
+    fn from(answer: &Answer) -> Self {
+        match answer {
+            Answer::Number(n) => Json::Number(*n),
+            Answer::Text(t) => Json::String(t.clone()),
+            Answer::Unsolved | Answer::Error(_) => Json::Null,
+        }
+    }
+}
+
+// times are in nanoseconds
+fn nanos(duration: std::time::Duration) -> Json {
+    Json::Number(duration.as_nanos() as u64)
+}
+
+pub fn part_report(report: &PartReport, status: &Status) -> Json {
+    let (status, expected) = match status {
+        Status::Pass => ("PASS", Json::Null),
+        Status::Fail { expected } => ("FAIL", Json::String(expected.clone())),
+        Status::Unknown => ("UNKNOWN", Json::Null),
+    };
+    Json::Object(vec![
+        ("day", Json::Number(report.day as u64)),
+        ("part", Json::Number(report.part as u64)),
+        ("answer", (&report.answer).into()),
+        ("error", match &report.answer {
+            Answer::Error(e) => Json::String(e.clone()),
+            _ => Json::Null,
+        }),
+        ("parse_ns", nanos(report.parse_time)),
+        ("solve_ns", nanos(report.solve_time)),
+        ("status", Json::String(status.to_string())),
+        ("expected", expected),
+    ])
+}
+
+pub fn bench_report(report: &BenchReport) -> Json {
+    let (phase, part) = match report.phase {
+        Phase::Parse => ("parse", Json::Null),
+        Phase::Part(n) => ("solve", Json::Number(n as u64)),
+    };
+    let stats = &report.stats;
+    Json::Object(vec![
+        ("day", Json::Number(report.day as u64)),
+        ("phase", Json::String(phase.to_string())),
+        ("part", part),
+        ("runs", Json::Number(stats.runs as u64)),
+        ("min_ns", nanos(stats.min)),
+        ("median_ns", nanos(stats.median)),
+        ("mean_ns", nanos(stats.mean)),
+        ("stddev_ns", nanos(stats.stddev)),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn escape_strings() {
+        let value = Json::Array(vec![Json::String("a \"b\"\n\\".to_string()), Json::Null]);
+        assert_eq!(value.to_string(), r#"["a \"b\"\n\\",null]"#);
+    }
+
+    #[test]
+    fn part_report_fields() {
+        let report = PartReport {
+            day: 7,
+            part: 2,
+            answer: Answer::Number(40),
+            parse_time: Duration::from_micros(3),
+            solve_time: Duration::from_nanos(250),
+        };
+        assert_eq!(
+            part_report(&report, &Status::Fail { expected: "41".to_string() }).to_string(),
+            r#"{"day":7,"part":2,"answer":40,"error":null,"parse_ns":3000,"solve_ns":250,"status":"FAIL","expected":"41"}"#
+        );
+        let report = PartReport { answer: Answer::Error("too small".to_string()), ..report };
+        assert_eq!(
+            part_report(&report, &Status::Unknown).to_string(),
+            r#"{"day":7,"part":2,"answer":null,"error":"too small","parse_ns":3000,"solve_ns":250,"status":"UNKNOWN","expected":null}"#
+        );
+    }
+}
@@ -0,0 +1,44 @@
+//! Advent of Code 2025 solutions.
+//!
+//! Every day is a module with its puzzle model, the functions solving it
+//! and a `Solver` implementing [`solution::Solution`] for the runner.
+//! The command line on top of it is the binary.
+
+pub mod answers;
+pub mod bench;
+pub mod input;
+pub mod parse;
+pub mod runner;
+pub mod solution;
+#[cfg(test)]
+mod testing;
+pub mod day_one;
+pub mod day_two;
+pub mod day_three;
+pub mod day_four;
+pub mod day_five;
+pub mod day_six;
+pub mod day_seven;
+pub mod day_eight;
+pub mod day_nine;
+pub mod day_ten;
+pub mod day_eleven;
+pub mod day_twelve;
+
+use runner::Day;
+
+/// Every day of the calendar, in order.
+pub const DAYS: [Day; 12] = [
+    Day::new::<day_one::Solver>(1),
+    Day::new::<day_two::Solver>(2),
+    Day::new::<day_three::Solver>(3),
+    Day::new::<day_four::Solver>(4),
+    Day::new::<day_five::Solver>(5),
+    Day::new::<day_six::Solver>(6),
+    Day::new::<day_seven::Solver>(7),
+    Day::new::<day_eight::Solver>(8),
+    Day::new::<day_nine::Solver>(9),
+    Day::new::<day_ten::Solver>(10),
+    Day::new::<day_eleven::Solver>(11),
+    Day::new::<day_twelve::Solver>(12),
+];
@@ -1,39 +1,29 @@
-mod day_one;
-mod day_two;
-mod day_three;
-mod day_four;
-mod day_five;
-mod day_six;
-mod day_seven;
-mod day_eight;
-mod day_nine;
-mod day_ten;
-mod day_eleven;
-mod day_twelve;
+mod cli;
+mod commands;
+mod json;
 
-use std::{fs::File, io::Read};
-use std::time::Instant;
-
-#[allow(unused)]
-enum InputMode {
-    Example,
-    Normal,
-}
-
-fn load_input(file_name: &str) -> String {
-    let mut input_file = File::open(file_name).unwrap();
-    let mut data = String::new();
-    input_file.read_to_string(&mut data).unwrap();
-    data
-}
+use cli::Command;
 
 fn main() {
-
-    let now = Instant::now();
-
-    day_ten::part_two();
-
-    let elapsed = now.elapsed();
-    println!("Elapsed: {:.2?}", elapsed);
+    match cli::parse_args(std::env::args().skip(1)) {
+        Ok(Command::Run(args)) => match commands::run(args) {
+            Ok(true) => {}
+            Ok(false) => std::process::exit(1),
+            Err(e) => {
+                eprintln!("Error: {e:#}");
+                std::process::exit(1);
+            }
+        },
+        Ok(Command::Bench(args)) => {
+            if let Err(e) = commands::bench(args) {
+                eprintln!("Error: {e:#}");
+                std::process::exit(1);
+            }
+        }
+        Ok(Command::Help) => println!("{}", cli::USAGE),
+        Err(e) => {
+            eprintln!("Error: {e:#}\n\n{}", cli::USAGE);
+            std::process::exit(2);
+        }
+    }
 }
-
@@ -0,0 +1,87 @@
+use std::fmt::Display;
+
+/// A malformed puzzle input, with the position of the offending text.
+/// Lines and columns start from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    /// Error at the position of `field`, which must be a slice of `text`.
+    pub fn at(text: &str, field: &str, message: impl Into<String>) -> Self {
+        let (line, column) = position(text, field);
+        Self { line, column, message: message.into() }
+    }
+
+    /// Move an error found in `inner`, a slice of `outer`,
+    /// to its position in `outer`.
+    pub fn within(mut self, outer: &str, inner: &str) -> Self {
+        let (line, column) = position(outer, inner);
+        if self.line == 1 {
+            self.column += column - 1;
+        }
+        self.line += line - 1;
+        self
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// line and column of `field` inside `text`,
+// a field outside of the text is reported at its end
+fn position(text: &str, field: &str) -> (usize, usize) {
+    let offset = (field.as_ptr() as usize)
+        .checked_sub(text.as_ptr() as usize)
+        .unwrap_or(text.len())
+        .min(text.len());
+    let before = &text[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |n| n + 1);
+    (line, before[line_start..].chars().count() + 1)
+}
+
+/// Parse every non blank line of the input with `parse_line`.
+pub fn parse_lines<T>(
+    input: &str,
+    mut parse_line: impl FnMut(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| parse_line(line).map_err(|e| e.within(input, line)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_position() {
+        let input = "12,4\n7,x\n";
+        let field = &input[7..8];
+        let error = ParseError::at(input, field, "not a number");
+        assert_eq!((error.line, error.column), (2, 3));
+        assert_eq!(error.to_string(), "line 2, column 3: not a number");
+    }
+
+    #[test]
+    fn error_in_line() {
+        let input = "12,4\n\n7,x\n";
+        let error = parse_lines(input, |line| {
+            let (_, y) = line.split_once(',').unwrap();
+            y.parse::<u32>().map_err(|_| ParseError::at(line, y, "not a number"))
+        })
+        .unwrap_err();
+        assert_eq!((error.line, error.column), (3, 3));
+    }
+}
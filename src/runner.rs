@@ -0,0 +1,71 @@
+use std::fmt::Display;
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+use crate::bench::{self, BenchFn};
+use crate::parse::ParseError;
+use crate::solution::{Answer, Solution, TraceFormat};
+
+pub type RunFn = fn(u8, &str, Option<u8>) -> Result<Vec<PartReport>, ParseError>;
+pub type TraceFn = fn(&str, TraceFormat, &mut dyn Write) -> anyhow::Result<bool>;
+
+/// A day of the calendar with its solver.
+pub struct Day {
+    pub number: u8,
+    pub run: RunFn,
+    pub bench: BenchFn,
+    pub trace: TraceFn,
+}
+
+impl Day {
+    pub const fn new<S: Solution>(number: u8) -> Self {
+        Self { number, run: run::<S>, bench: bench::bench::<S>, trace: trace::<S> }
+    }
+}
+
+/// The outcome of a single puzzle part.
+pub struct PartReport {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Answer,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+}
+
+impl Display for PartReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Day {:>2} - part {}: {:<20} (parse {:.2?}, solve {:.2?})",
+            self.day, self.part, self.answer.to_string(), self.parse_time, self.solve_time
+        )
+    }
+}
+
+// parse the input once, then solve the selected part (both when None)
+pub fn run<S: Solution>(day: u8, input: &str, part: Option<u8>) -> Result<Vec<PartReport>, ParseError> {
+    let now = Instant::now();
+    let model = S::parse(input)?;
+    let parse_time = now.elapsed();
+
+    let reports = [1, 2]
+        .into_iter()
+        .filter(|n| part.is_none_or(|p| p == *n))
+        .map(|n| {
+            let now = Instant::now();
+            let answer = match n {
+                1 => S::part_one(&model),
+                _ => S::part_two(&model),
+            };
+            let solve_time = now.elapsed();
+            PartReport { day, part: n, answer, parse_time, solve_time }
+        })
+        .collect();
+    Ok(reports)
+}
+
+// false when the day has no trace
+pub fn trace<S: Solution>(input: &str, format: TraceFormat, out: &mut dyn Write) -> anyhow::Result<bool> {
+    let model = S::parse(input)?;
+    Ok(S::trace(&model, format, out)?)
+}
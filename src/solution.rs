@@ -0,0 +1,78 @@
+use std::fmt::Display;
+use std::io::{self, Write};
+
+use crate::parse::ParseError;
+
+/// The value produced by a puzzle part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Number(u64),
+    Text(String),
+    /// the part has no solution implemented (yet)
+    Unsolved,
+    /// the input has no answer for this part, with the reason
+    Error(String),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{n}"),
+            Answer::Text(t) => write!(f, "{t}"),
+            Answer::Unsolved => write!(f, "unsolved"),
+            Answer::Error(e) => write!(f, "error: {e}"),
+        }
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(value: u64) -> Self {
+        Answer::Number(value)
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(value: u32) -> Self {
+        Answer::Number(value as u64)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Answer::Number(value as u64)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+/// How a solution trace is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TraceFormat {
+    #[default]
+    Text,
+    /// text highlighted with ANSI colours, plain text for the days without colours
+    Color,
+    Csv,
+}
+
+/// Every day implements this trait: the input is parsed once into
+/// a typed model, then both parts are solved from the same model.
+pub trait Solution {
+    type Model;
+
+    fn parse(input: &str) -> Result<Self::Model, ParseError>;
+
+    fn part_one(model: &Self::Model) -> Answer;
+
+    fn part_two(model: &Self::Model) -> Answer;
+
+    /// Write the steps taken to solve the puzzle, returns false
+    /// when the day has nothing to trace.
+    fn trace(_model: &Self::Model, _format: TraceFormat, _out: &mut dyn Write) -> io::Result<bool> {
+        Ok(false)
+    }
+}
@@ -0,0 +1,26 @@
+//! Helpers shared by the tests of the days.
+
+/// A xorshift generator: enough to shuffle the cases of the randomized
+/// tests without a dependency, with the same cases on every run.
+pub struct XorShift {
+    state: u64,
+}
+
+impl XorShift {
+    pub fn new(seed: u64) -> Self {
+        assert!(seed != 0, "a xorshift generator can't start from 0");
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    /// A number from 0 to `n` excluded.
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+}